@@ -0,0 +1,190 @@
+//! Eventos que publica el contrato para que los indexadores puedan seguir cada votación.
+//!
+//! El primer topic siempre es el nombre del evento; si el evento es de una propuesta,
+//! el segundo es su id.
+use soroban_sdk::{symbol_short, Address, BytesN, Env, Vec};
+
+use crate::{BallotState, FeeConfig, Outcome, Role};
+
+pub(crate) fn initialized(env: &Env, admin: &Address, token: &Option<Address>) {
+    env.events()
+        .publish((symbol_short!("init"), admin.clone()), token.clone());
+}
+
+pub(crate) fn proposal_created(env: &Env, proposal_id: u32, creator: &Address) {
+    env.events()
+        .publish((symbol_short!("created"), proposal_id), creator.clone());
+}
+
+pub(crate) fn voted(env: &Env, proposal_id: u32, voter: &Address, option: u32, weight: i128) {
+    env.events().publish(
+        (symbol_short!("vote"), proposal_id, voter.clone()),
+        (option, weight),
+    );
+}
+
+pub(crate) fn committed(env: &Env, proposal_id: u32, voter: &Address) {
+    env.events()
+        .publish((symbol_short!("commit"), proposal_id), voter.clone());
+}
+
+pub(crate) fn vote_changed(
+    env: &Env,
+    proposal_id: u32,
+    voter: &Address,
+    old_option: u32,
+    new_option: u32,
+    weight: i128,
+) {
+    env.events().publish(
+        (symbol_short!("vote_chg"), proposal_id, voter.clone()),
+        (old_option, new_option, weight),
+    );
+}
+
+pub(crate) fn vote_retracted(
+    env: &Env,
+    proposal_id: u32,
+    voter: &Address,
+    option: u32,
+    weight: i128,
+) {
+    env.events().publish(
+        (symbol_short!("retract"), proposal_id, voter.clone()),
+        (option, weight),
+    );
+}
+
+pub(crate) fn state_changed(env: &Env, proposal_id: u32, from: BallotState, to: BallotState) {
+    env.events()
+        .publish((symbol_short!("state"), proposal_id), (from, to));
+}
+
+pub(crate) fn closed(env: &Env, proposal_id: u32, outcome: &Outcome) {
+    env.events()
+        .publish((symbol_short!("closed"), proposal_id), outcome.clone());
+}
+
+pub(crate) fn close_approved(env: &Env, proposal_id: u32, signer: &Address, approvals: u32) {
+    env.events().publish(
+        (symbol_short!("close_ok"), proposal_id, signer.clone()),
+        approvals,
+    );
+}
+
+pub(crate) fn deposit_settled(
+    env: &Env,
+    proposal_id: u32,
+    recipient: &Address,
+    amount: i128,
+    refunded: bool,
+) {
+    env.events().publish(
+        (symbol_short!("deposit"), proposal_id, recipient.clone()),
+        (amount, refunded),
+    );
+}
+
+pub(crate) fn queued(env: &Env, proposal_id: u32, eta: u64) {
+    env.events()
+        .publish((symbol_short!("queued"), proposal_id), eta);
+}
+
+pub(crate) fn vetoed(env: &Env, proposal_id: u32, guardian: &Address) {
+    env.events()
+        .publish((symbol_short!("vetoed"), proposal_id), guardian.clone());
+}
+
+pub(crate) fn executed(env: &Env, proposal_id: u32) {
+    env.events()
+        .publish((symbol_short!("executed"), proposal_id), ());
+}
+
+pub(crate) fn tokens_unlocked(env: &Env, proposal_id: u32, voter: &Address, amount: i128) {
+    env.events().publish(
+        (symbol_short!("unlock"), proposal_id, voter.clone()),
+        amount,
+    );
+}
+
+pub(crate) fn voters_added(env: &Env, proposal_id: u32, voters: &Vec<Address>) {
+    env.events()
+        .publish((symbol_short!("elig_add"), proposal_id), voters.clone());
+}
+
+pub(crate) fn voter_removed(env: &Env, proposal_id: u32, voter: &Address) {
+    env.events()
+        .publish((symbol_short!("elig_rm"), proposal_id), voter.clone());
+}
+
+pub(crate) fn delegated(env: &Env, proposal_id: u32, from: &Address, to: &Address) {
+    env.events().publish(
+        (symbol_short!("delegate"), proposal_id, from.clone()),
+        to.clone(),
+    );
+}
+
+pub(crate) fn delegation_revoked(env: &Env, proposal_id: u32, from: &Address, to: &Address) {
+    env.events().publish(
+        (symbol_short!("undelegat"), proposal_id, from.clone()),
+        to.clone(),
+    );
+}
+
+pub(crate) fn voter_key_registered(env: &Env, voter: &Address, public_key: &BytesN<32>) {
+    env.events().publish(
+        (symbol_short!("voter_key"), voter.clone()),
+        public_key.clone(),
+    );
+}
+
+pub(crate) fn admin_proposed(env: &Env, admin: &Address, new_admin: &Address) {
+    env.events().publish(
+        (symbol_short!("adm_prop"), admin.clone()),
+        new_admin.clone(),
+    );
+}
+
+pub(crate) fn admin_changed(env: &Env, old_admin: &Address, new_admin: &Address) {
+    env.events().publish(
+        (symbol_short!("adm_set"), old_admin.clone()),
+        new_admin.clone(),
+    );
+}
+
+pub(crate) fn role_granted(env: &Env, account: &Address, role: Role) {
+    env.events()
+        .publish((symbol_short!("role_add"), account.clone()), role);
+}
+
+pub(crate) fn role_revoked(env: &Env, account: &Address, role: Role) {
+    env.events()
+        .publish((symbol_short!("role_rm"), account.clone()), role);
+}
+
+pub(crate) fn fees_set(env: &Env, admin: &Address, fees: &FeeConfig) {
+    env.events()
+        .publish((symbol_short!("fees"), admin.clone()), fees.clone());
+}
+
+pub(crate) fn fees_removed(env: &Env, admin: &Address) {
+    env.events()
+        .publish((symbol_short!("fees_rm"), admin.clone()), ());
+}
+
+pub(crate) fn min_timelock_set(env: &Env, admin: &Address, delay: u64) {
+    env.events()
+        .publish((symbol_short!("timelock"), admin.clone()), delay);
+}
+
+pub(crate) fn upgraded(env: &Env, admin: &Address, new_wasm_hash: &BytesN<32>) {
+    env.events().publish(
+        (symbol_short!("upgrade"), admin.clone()),
+        new_wasm_hash.clone(),
+    );
+}
+
+pub(crate) fn migrated(env: &Env, from_version: u32, to_version: u32, votes: u32) {
+    env.events()
+        .publish((symbol_short!("migrate"), from_version, to_version), votes);
+}
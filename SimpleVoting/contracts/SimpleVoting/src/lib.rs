@@ -1,163 +1,2779 @@
 #![no_std]
-use soroban_sdk::{contract, contractimpl, contracttype, log, symbol_short, Address, Env, Symbol};
-// Solo almacenamos lo básico
+use soroban_sdk::xdr::ToXdr;
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, log, token, Address, Bytes, BytesN, Env,
+    String, Symbol, Val, Vec,
+};
+
+mod events;
+mod storage;
+
+// `Admin`, `PendingAdmin`, `Token`, `Fees`, `MinTimelock`, `ProposalCount` y `Version` van en
+// instance; el resto en persistent (ver `storage`)
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
-
-// Solo almacenamos lo básico
 pub enum DataKey {
-    // Quien creó la votación
+    // Administrador del contrato
+    Admin,
+    // Administrador propuesto, pendiente de aceptar
+    PendingAdmin,
+    // Si una dirección tiene un rol
+    Role(Address, Role),
+    // Token de gobernanza opcional con el que se ponderan los votos
+    Token,
+    // Depósito por propuesta y tarifa por voto, si el administrador los configuró
+    Fees,
+    // Espera mínima en cola antes de ejecutar, fijada por el administrador
+    MinTimelock,
+    // Cuántas propuestas se han creado (también es el id de la siguiente)
+    ProposalCount,
+    // Versión del esquema de almacenamiento (ver `SCHEMA_VERSION`)
+    Version,
+    // Quien creó cada propuesta
+    Creator(u32),
+    // Estado del ciclo de vida de la propuesta
+    State(u32),
+    // Configuración de la propuesta (opciones y ventana de votación)
+    Config(u32),
+    // Cuántos votos tiene cada opción (propuesta, índice de opción)
+    Votes(u32, u32),
+    // Qué votó una persona en la propuesta (si existe, ya votó)
+    HasVoted(u32, Address),
+    // Peso en tokens con el que un votante participa en la propuesta
+    Locked(u32, Address),
+    // Tokens que el contrato guarda para un votante, compartidos entre propuestas
+    Escrow(Address),
+    // Resultado final de la propuesta, calculado al cerrarla
+    Outcome(u32),
+    // Si una dirección está en la lista de votantes de la propuesta
+    Eligible(u32, Address),
+    // En quién delegó su voto una dirección
+    Delegate(u32, Address),
+    // Peso total que otros delegaron, directa o indirectamente, en una dirección
+    DelegatedWeight(u32, Address),
+    // Delegaciones de la cadena más larga que termina en una dirección
+    DelegationHeight(u32, Address),
+    // Compromiso de voto secreto pendiente de revelar: sha256(opción || sal)
+    Commitment(u32, Address),
+    // Firmantes del comité que ya aprobaron cerrar la propuesta
+    CloseApprovals(u32),
+    // Preferencias ordenadas de un votante en una propuesta por ranking
+    Ranking(u32, Address),
+    // Cuántos votantes tiene una propuesta por ranking
+    RankedVoterCount(u32),
+    // Votante de una propuesta por ranking (propuesta, posición), para el recuento
+    RankedVoter(u32, u32),
+    // Recuento por ranking en curso, que avanza por páginas tras cerrar la propuesta
+    IrvProgress(u32),
+    // Recuento por ranking terminado, con el ganador y los votos de cada ronda
+    IrvTally(u32),
+    // Votos que un votante dio a una opción en una propuesta cuadrática
+    QuadraticVotes(u32, Address, u32),
+    // Créditos que un votante ya gastó en una propuesta cuadrática
+    CreditsSpent(u32, Address),
+    // Cuántas entradas tiene el registro de votos de la propuesta
+    VoterLogLen(u32),
+    // Entrada del registro de votos (propuesta, posición)
+    VoterLog(u32, u32),
+    // Depósito bloqueado al crear la propuesta, pendiente de devolver o retener
+    Deposit(u32),
+    // Si ya se ejecutaron las acciones de la propuesta
+    Executed(u32),
+    // Cola del timelock: cuándo se pueden ejecutar las acciones, o si se vetaron
+    Queue(u32),
+    // Clave pública ed25519 con la que una dirección firma votos fuera de la cadena
+    VoterKey(Address),
+    // Siguiente nonce que debe usar una dirección en sus votos firmados
+    Nonce(Address),
+    // Si algún votante ya liberó los tokens con los que votó en la propuesta
+    Unlocked(u32),
+}
+
+// Claves del contrato original de una sola votación Si/No, todas en instance.
+// Solo se leen en `migrate` para pasarlas al esquema actual.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum LegacyDataKey {
     Creator,
-    // Si la votación está activa
     Active,
-    // Cuántos votos tiene "SI"
     VotesSi,
-    // Cuántos votos tiene "NO"
     VotesNo,
-    // Si una persona ya votó
     HasVoted(Address),
 }
 
+/// Versión del esquema de almacenamiento que escribe este código
+/// (0 es el contrato original de una sola votación)
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Opción de los votos migrados del contrato original, que no guardaba qué se votó
+pub const UNKNOWN_OPTION: u32 = u32::MAX;
+
+/// Máximo de opciones que puede tener una propuesta
+pub const MAX_OPTIONS: u32 = 16;
+
+/// Máximo de opciones de una propuesta por ranking, para acotar el recuento on-chain
+pub const MAX_RANKED_OPTIONS: u32 = 8;
+
+/// Máximo de bytes del título de una propuesta
+pub const MAX_TITLE_LEN: u32 = 100;
+
+/// Máximo de bytes de la descripción (o URI) de una propuesta
+pub const MAX_DESCRIPTION_LEN: u32 = 1_000;
+
+/// Máximo de entradas que devuelve `list_voters` por llamada
+pub const MAX_PAGE_SIZE: u32 = 100;
+
+/// 100% expresado en puntos básicos
+pub const BPS_DENOMINATOR: u32 = 10_000;
+
+/// Espera máxima en cola antes de ejecutar, en segundos (un año)
+pub const MAX_TIMELOCK: u64 = 365 * 24 * 60 * 60;
+
+/// Máximo de delegaciones en una cadena, se alargue por el principio o por el final
+pub const MAX_DELEGATION_DEPTH: u32 = 8;
+
+/// Configuración con la que se crea una propuesta
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProposalConfig {
+    /// Título corto de lo que se vota
+    pub title: String,
+    /// Descripción o URI del documento completo
+    pub description: String,
+    /// Hash del documento completo (por ejemplo sha256), para comprobar que no cambió
+    pub content_hash: BytesN<32>,
+    /// Opciones por las que se puede votar
+    pub options: Vec<String>,
+    /// Timestamp del ledger a partir del cual se puede votar
+    pub start_time: u64,
+    /// Timestamp del ledger a partir del cual ya no se puede votar (sin límite si es `None`)
+    pub end_time: Option<u64>,
+    /// Participación mínima (suma de pesos de los votos) para que el resultado sea válido
+    pub quorum: i128,
+    /// Porcentaje en puntos básicos que la opción ganadora debe superar:
+    /// 5_000 es mayoría simple, 6_666 son dos tercios
+    pub threshold_bps: u32,
+    /// Si es `true`, solo votan las direcciones que el creador añada a la lista de votantes
+    pub restricted: bool,
+    /// Si es `true`, se puede cambiar o retirar el voto mientras la votación esté abierta
+    pub allow_vote_change: bool,
+    /// Si es `true`, la propuesta se crea en `Draft` y no acepta votos hasta abrirla
+    pub draft: bool,
+    /// Si se indica, el voto es secreto: hasta `end_time` se envían compromisos con `commit`
+    /// y desde `end_time` hasta este timestamp se revelan con `reveal`
+    pub reveal_end_time: Option<u64>,
+    /// Comité de cierre: si no está vacío, la propuesta solo se cierra cuando
+    /// `committee_threshold` de estas direcciones lo aprueban con `approve_close`
+    pub committee: Vec<Address>,
+    /// Aprobaciones necesarias para cerrar (entre 1 y el tamaño del comité; 0 sin comité)
+    pub committee_threshold: u32,
+    /// Si es `true`, se vota con `vote_ranked` ordenando las opciones y gana quien tenga
+    /// mayoría tras la segunda vuelta instantánea (`tally_irv`); `threshold_bps` no se usa
+    pub ranked: bool,
+    /// Si es mayor que 0, la votación es cuadrática: cada votante tiene estos créditos y
+    /// los reparte con `vote_quadratic`, donde n votos a una misma opción cuestan n².
+    /// Necesita `restricted`.
+    pub credits: u32,
+    /// Llamadas a otros contratos que `execute` hace, en orden, si gana la primera opción
+    pub actions: Vec<ProposalAction>,
+    /// Segundos que las acciones esperan en cola (`queue`) antes de poder ejecutarse, para
+    /// que un `Role::Guardian` pueda vetarlas. Nunca menos que el mínimo del contrato
+    /// (`set_min_timelock`) ni más de `MAX_TIMELOCK`.
+    pub timelock: u64,
+}
+
+/// Llamada a otro contrato que se hace al aprobarse una propuesta
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProposalAction {
+    /// Contrato al que se llama
+    pub contract: Address,
+    /// Función del contrato
+    pub function: Symbol,
+    /// Argumentos de la llamada
+    pub args: Vec<Val>,
+}
+
+/// Datos de una propuesta
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Proposal {
+    /// Quién la creó
+    pub creator: Address,
+    /// Estado actual (`Closed` también si ya pasó su último plazo)
+    pub state: BallotState,
+    /// Configuración con la que se creó, incluidos título, descripción y hash
+    pub config: ProposalConfig,
+}
+
+/// Recuento de una propuesta por ranking
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IrvTally {
+    /// Opción con mayoría absoluta en la última ronda (`None` si no hay votos o hay empate)
+    pub winner: Option<u32>,
+    /// Votos de cada opción en cada ronda; las eliminadas cuentan 0
+    pub rounds: Vec<Vec<i128>>,
+}
+
+/// Estado de un recuento por ranking a medias: la ronda en curso y las ya cerradas
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct IrvProgress {
+    /// Siguiente votante que hay que contar en la ronda en curso
+    next: u32,
+    counts: Vec<i128>,
+    eliminated: Vec<bool>,
+    rounds: Vec<Vec<i128>>,
+}
+
+/// Roles que el administrador puede asignar además del creador de cada propuesta
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Role {
+    /// Puede cerrar, reabrir y cancelar cualquier propuesta
+    Closer,
+    /// Puede gestionar la lista de votantes y abrir, pausar o reanudar cualquier propuesta
+    Moderator,
+    /// Puede vetar las acciones de una propuesta mientras esperan en el timelock
+    Guardian,
+}
+
+/// Situación de las acciones de una propuesta aprobada en el timelock
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum QueueState {
+    /// Todavía no se pusieron en cola
+    NotQueued,
+    /// En cola; se pueden ejecutar desde este timestamp
+    Queued(u64),
+    /// Un guardián las vetó y ya no se ejecutarán
+    Vetoed,
+    /// Ya se ejecutaron
+    Executed,
+}
+
+/// Depósito por propuesta y tarifa por voto contra el spam
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeConfig {
+    /// Token en el que se pagan el depósito y la tarifa
+    pub token: Address,
+    /// Cantidad que bloquea quien crea una propuesta (0 sin depósito)
+    pub deposit: i128,
+    /// Cantidad que paga cada voto a la tesorería (0 sin tarifa)
+    pub vote_fee: i128,
+    /// Quien recibe las tarifas y los depósitos retenidos
+    pub treasury: Address,
+}
+
+/// Depósito bloqueado por una propuesta, con los datos vigentes al crearla
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Deposit {
+    pub token: Address,
+    pub amount: i128,
+    pub treasury: Address,
+}
+
+/// Tokens de gobernanza que el contrato guarda para un votante. Cuentan en todas las
+/// propuestas en las que participa y solo se devuelven cuando ninguna sigue abierta.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Escrow {
+    pub amount: i128,
+    /// Propuestas que todavía usan estos tokens
+    pub proposals: u32,
+}
+
+/// Voto firmado fuera de la cadena que un relayer envía en nombre del votante
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SignedVote {
+    /// Quién vota
+    pub voter: Address,
+    /// Opción elegida
+    pub option: u32,
+    /// Nonce del votante (ver `get_nonce`)
+    pub nonce: u64,
+    /// Firma ed25519 de `vote_message(proposal_id, option, nonce)`
+    pub signature: BytesN<64>,
+}
+
+/// Voto registrado de una dirección
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VoteRecord {
+    /// Opción elegida
+    pub option: u32,
+    /// Peso con el que contó, incluidas las delegaciones
+    pub weight: i128,
+}
+
+/// Entrada del registro de votos de una propuesta
+///
+/// Cada cambio en el recuento añade una: votar suma `weight` a `option`, y cambiar o
+/// retirar un voto añade otra con el peso en negativo. Sumando `weight` por opción se
+/// obtienen los votos de `get_results`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VoterEntry {
+    /// Quién votó
+    pub voter: Address,
+    /// Opción a la que se sumó (o restó) el peso
+    pub option: u32,
+    /// Peso sumado a la opción; negativo al cambiar o retirar el voto
+    pub weight: i128,
+    /// Secuencia del ledger en la que se registró
+    pub ledger: u32,
+}
+
+/// Resultado final de una propuesta
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Outcome {
+    /// La votación todavía no se ha cerrado
+    Pending,
+    /// La opción indicada superó el umbral (en Si/No, `Passed(0)` es aprobada)
+    Passed(u32),
+    /// Ninguna opción superó el umbral
+    Rejected,
+    /// No se alcanzó la participación mínima
+    QuorumNotMet,
+    /// La propuesta se canceló
+    Cancelled,
+}
+
+/// Ciclo de vida de una propuesta
+///
+/// Draft -> Open <-> Paused; Open/Paused -> Closed -> Open (reabrir);
+/// cualquier estado salvo Closed -> Cancelled
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BallotState {
+    /// Creada pero todavía sin abrir
+    Draft,
+    /// Acepta votos dentro de su ventana
+    Open,
+    /// Suspendida temporalmente, por ejemplo por un incidente
+    Paused,
+    /// Cerrada con resultado, o terminada su ventana
+    Closed,
+    /// Anulada; no tiene resultado
+    Cancelled,
+}
+
+/// Resultados de una propuesta
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Results {
+    /// Votos de cada opción, en el mismo orden que las opciones
+    /// (ponderados por balance si hay token de gobernanza)
+    pub votes: Vec<i128>,
+    /// Estado de la propuesta (`Closed` también si ya pasó su último plazo)
+    pub state: BallotState,
+    /// Ventana de votación configurada en la propuesta
+    pub start_time: u64,
+    pub end_time: Option<u64>,
+}
+
+#[contracterror]
+#[derive(Clone, Debug, Copy, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    /// El contrato ya ha sido inicializado.
+    AlreadyInitialized = 1,
+    /// El contrato no ha sido inicializado.
+    NotInitialized = 2,
+    /// El período de votación no está activo.
+    VotingNotActive = 3,
+    /// La dirección ya ha votado.
+    AlreadyVoted = 4,
+    /// Quien llama no es el creador de la propuesta, el administrador ni tiene el rol necesario.
+    Unauthorized = 5,
+    /// La propuesta no existe.
+    ProposalNotFound = 6,
+    /// La propuesta necesita entre 2 y `MAX_OPTIONS` opciones.
+    InvalidOptions = 7,
+    /// El índice de opción no existe en la propuesta.
+    InvalidOption = 8,
+    /// La ventana de votación termina antes de empezar, o el timelock supera
+    /// `MAX_TIMELOCK`.
+    InvalidWindow = 9,
+    /// El votante no tiene balance del token de gobernanza.
+    NoVotingPower = 10,
+    /// Los tokens siguen bloqueados hasta que termine la votación.
+    VotingStillOpen = 11,
+    /// No hay tokens bloqueados para esta dirección.
+    NothingLocked = 12,
+    /// El umbral debe estar entre 1 y 10_000 puntos básicos y el quórum no puede ser negativo.
+    InvalidThreshold = 13,
+    /// La dirección no está en la lista de votantes de la propuesta.
+    NotEligible = 14,
+    /// La dirección delegó su voto; debe revocar la delegación para votar.
+    AlreadyDelegated = 15,
+    /// La delegación formaría un ciclo.
+    DelegationCycle = 16,
+    /// La cadena de delegaciones supera `MAX_DELEGATION_DEPTH`.
+    DelegationTooDeep = 17,
+    /// El final de la cadena de delegaciones ya votó.
+    DelegateAlreadyVoted = 18,
+    /// La dirección no ha delegado su voto.
+    NotDelegated = 19,
+    /// La propuesta no permite cambiar el voto.
+    VoteChangeNotAllowed = 20,
+    /// La dirección no ha votado en la propuesta.
+    NotVoted = 21,
+    /// La operación no está disponible en una votación secreta (o solo lo está en ellas).
+    SecretBallotMismatch = 22,
+    /// El período para revelar votos no está activo.
+    RevealNotActive = 23,
+    /// La opción y la sal no coinciden con el compromiso enviado.
+    InvalidReveal = 24,
+    /// Quien llama no es el administrador del contrato.
+    NotAdmin = 25,
+    /// No hay un administrador propuesto o quien acepta no es el propuesto.
+    NoPendingAdmin = 26,
+    /// La propuesta no puede pasar a ese estado desde el actual.
+    InvalidTransition = 27,
+    /// El almacenamiento tiene una versión de esquema más nueva que este código.
+    UnsupportedVersion = 28,
+    /// El comité no tiene firmantes únicos o su umbral no está entre 1 y su tamaño.
+    InvalidCommittee = 29,
+    /// La propuesta no tiene comité de cierre.
+    NoCommittee = 30,
+    /// El firmante ya aprobó el cierre.
+    AlreadyApproved = 31,
+    /// La propuesta solo se puede cerrar con las aprobaciones de su comité.
+    CommitteeRequired = 32,
+    /// El tipo de voto no corresponde a la propuesta (por ranking o de una sola opción),
+    /// o la propuesta por ranking pide algo que no admite.
+    RankedBallotMismatch = 33,
+    /// El ranking está vacío, repite opciones o incluye opciones que no existen.
+    InvalidRanking = 34,
+    /// El tipo de voto no corresponde a la propuesta (cuadrática o no), o la propuesta
+    /// cuadrática pide algo que no admite.
+    QuadraticBallotMismatch = 35,
+    /// El votante no tiene créditos suficientes para esos votos.
+    InsufficientCredits = 36,
+    /// El título o la descripción superan su longitud máxima.
+    MetadataTooLong = 37,
+    /// El depósito o la tarifa son negativos, o la tarifa por voto se cobra en el token de
+    /// gobernanza (al votar se bloquea todo el balance y no quedaría nada para pagarla).
+    InvalidFee = 38,
+    /// No hay depósito ni tarifa configurados.
+    NoFees = 39,
+    /// La propuesta no se aprobó: no está cerrada o no ganó la primera opción.
+    NotPassed = 40,
+    /// Las acciones de la propuesta ya se ejecutaron.
+    AlreadyExecuted = 41,
+    /// La propuesta no tiene acciones que ejecutar.
+    NoActions = 42,
+    /// Las acciones no están en cola.
+    NotQueued = 43,
+    /// Las acciones ya están en cola o se vetaron.
+    AlreadyQueued = 44,
+    /// Todavía no terminó el timelock.
+    TimelockNotExpired = 45,
+    /// Un guardián vetó las acciones de la propuesta.
+    Vetoed = 46,
+    /// El votante no registró una clave pública para votos firmados.
+    NoVoterKey = 47,
+    /// El nonce del voto firmado no es el siguiente del votante.
+    InvalidNonce = 48,
+    /// Los votos firmados no se admiten con token de gobernanza: bloquearlo necesita la
+    /// firma del votante en la transacción.
+    RelayNotAllowed = 49,
+    /// La propuesta no tiene un recuento por ranking pendiente.
+    NoPendingTally = 50,
+}
+
+/// Todos los errores del contrato salen por aquí. Con la feature `legacy` el contrato
+/// aborta con un panic, como la versión original del taller, en lugar de devolver el
+/// código de error al cliente.
+fn fail(error: Error) -> Error {
+    #[cfg(feature = "legacy")]
+    panic!("{:?}", error);
+    #[cfg(not(feature = "legacy"))]
+    error
+}
+
 #[contract]
 pub struct SimpleVoting;
 
 #[contractimpl]
 impl SimpleVoting {
-    /// Inicializar la votación (solo una vez)
-    pub fn init(env: Env, creator: Address) {
-        // El creador debe autorizar
-        creator.require_auth();
+    /// Inicializar el contrato (solo una vez)
+    ///
+    /// Si se indica `token`, cada voto pesa el balance del votante en ese token.
+    pub fn init(env: Env, admin: Address, token: Option<Address>) -> Result<(), Error> {
+        // Un contrato con datos del esquema original se inicializa con `migrate`
+        if env.storage().instance().has(&DataKey::Admin)
+            || env.storage().instance().has(&LegacyDataKey::Creator)
+        {
+            return Err(fail(Error::AlreadyInitialized));
+        }
 
-        log!(&env, "Iniciando votación UUUUUUUUUUU, creador: {}", creator);
+        // El administrador debe autorizar
+        admin.require_auth();
+        storage::extend_instance(&env);
 
-        // Guardar datos iniciales
-        env.storage().instance().set(&DataKey::Creator, &creator);
-        env.storage().instance().set(&DataKey::Active, &true);
-        env.storage().instance().set(&DataKey::VotesSi, &0u32);
-        env.storage().instance().set(&DataKey::VotesNo, &0u32);
+        log!(&env, "Inicializando contrato, administrador: {}", admin);
+
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        if let Some(token) = &token {
+            env.storage().instance().set(&DataKey::Token, token);
+        }
+        env.storage().instance().set(&DataKey::ProposalCount, &0u32);
+        env.storage()
+            .instance()
+            .set(&DataKey::Version, &SCHEMA_VERSION);
 
-        log!(&env, "Votación inicializada correctamente");
+        events::initialized(&env, &admin, &token);
+        log!(&env, "Contrato inicializado correctamente");
+        Ok(())
     }
-    /// Votar SI
-    pub fn vote_si(env: Env, voter: Address) {
-        // El votante debe autorizar
-        voter.require_auth();
 
-        log!(&env, "Usuario {} votando SI", voter);
+    /// Proponer un nuevo administrador; el cambio se hace efectivo cuando lo acepta
+    pub fn propose_admin(env: Env, admin: Address, new_admin: Address) -> Result<(), Error> {
+        admin.require_auth();
+        storage::extend_instance(&env);
+        Self::_require_admin(&env, &admin)?;
+
+        env.storage()
+            .instance()
+            .set(&DataKey::PendingAdmin, &new_admin);
+
+        events::admin_proposed(&env, &admin, &new_admin);
+        log!(&env, "Administrador propuesto: {}", new_admin);
+        Ok(())
+    }
+
+    /// Aceptar la administración del contrato (solo el administrador propuesto)
+    pub fn accept_admin(env: Env, new_admin: Address) -> Result<(), Error> {
+        new_admin.require_auth();
+        storage::extend_instance(&env);
 
-        // Verificar que la votación esté activa
-        let active: bool = env
+        let pending: Address = env
             .storage()
             .instance()
-            .get(&DataKey::Active)
-            .unwrap_or(false);
+            .get(&DataKey::PendingAdmin)
+            .ok_or_else(|| fail(Error::NoPendingAdmin))?;
+        if pending != new_admin {
+            return Err(fail(Error::NoPendingAdmin));
+        }
 
-        if !active {
-            panic!("La votación no está activa");
+        let old_admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or_else(|| fail(Error::NotInitialized))?;
+
+        env.storage().instance().set(&DataKey::Admin, &new_admin);
+        env.storage().instance().remove(&DataKey::PendingAdmin);
+
+        events::admin_changed(&env, &old_admin, &new_admin);
+        log!(&env, "Nuevo administrador: {}", new_admin);
+        Ok(())
+    }
+
+    /// Dar un rol a una dirección (solo el administrador)
+    pub fn grant_role(env: Env, admin: Address, account: Address, role: Role) -> Result<(), Error> {
+        admin.require_auth();
+        storage::extend_instance(&env);
+        Self::_require_admin(&env, &admin)?;
+
+        storage::set(&env, &DataKey::Role(account.clone(), role), &true);
+
+        events::role_granted(&env, &account, role);
+        log!(&env, "Rol {:?} asignado a {}", role, account);
+        Ok(())
+    }
+
+    /// Quitar un rol a una dirección (solo el administrador)
+    pub fn revoke_role(
+        env: Env,
+        admin: Address,
+        account: Address,
+        role: Role,
+    ) -> Result<(), Error> {
+        admin.require_auth();
+        storage::extend_instance(&env);
+        Self::_require_admin(&env, &admin)?;
+
+        storage::remove(&env, &DataKey::Role(account.clone(), role));
+
+        events::role_revoked(&env, &account, role);
+        log!(&env, "Rol {:?} retirado a {}", role, account);
+        Ok(())
+    }
+
+    /// Configurar el depósito por propuesta y la tarifa por voto (solo el administrador)
+    ///
+    /// Solo afecta a las propuestas y votos posteriores; cada propuesta guarda su depósito.
+    /// Con token de gobernanza, la tarifa por voto tiene que cobrarse en otro token.
+    pub fn set_fees(env: Env, admin: Address, fees: FeeConfig) -> Result<(), Error> {
+        admin.require_auth();
+        storage::extend_instance(&env);
+        Self::_require_admin(&env, &admin)?;
+
+        if fees.deposit < 0 || fees.vote_fee < 0 {
+            return Err(fail(Error::InvalidFee));
+        }
+        if fees.vote_fee > 0
+            && env.storage().instance().get::<_, Address>(&DataKey::Token)
+                == Some(fees.token.clone())
+        {
+            return Err(fail(Error::InvalidFee));
+        }
+        env.storage().instance().set(&DataKey::Fees, &fees);
+
+        events::fees_set(&env, &admin, &fees);
+        log!(
+            &env,
+            "Depósito {} y tarifa por voto {}",
+            fees.deposit,
+            fees.vote_fee
+        );
+        Ok(())
+    }
+
+    /// Dejar de cobrar depósito y tarifa (solo el administrador)
+    pub fn remove_fees(env: Env, admin: Address) -> Result<(), Error> {
+        admin.require_auth();
+        storage::extend_instance(&env);
+        Self::_require_admin(&env, &admin)?;
+
+        env.storage().instance().remove(&DataKey::Fees);
+        events::fees_removed(&env, &admin);
+        log!(&env, "Depósito y tarifa eliminados");
+        Ok(())
+    }
+
+    /// Fijar la espera mínima en cola de todas las propuestas, en segundos y hasta
+    /// `MAX_TIMELOCK` (solo el administrador). Se aplica a las que se pongan en cola a partir de ahora.
+    pub fn set_min_timelock(env: Env, admin: Address, delay: u64) -> Result<(), Error> {
+        admin.require_auth();
+        storage::extend_instance(&env);
+        Self::_require_admin(&env, &admin)?;
+        if delay > MAX_TIMELOCK {
+            return Err(fail(Error::InvalidWindow));
+        }
+
+        env.storage().instance().set(&DataKey::MinTimelock, &delay);
+
+        events::min_timelock_set(&env, &admin, delay);
+        log!(&env, "Espera mínima en cola: {} segundos", delay);
+        Ok(())
+    }
+
+    /// Reemplazar el código del contrato por otro wasm ya subido (solo el administrador)
+    ///
+    /// El almacenamiento se conserva; si el nuevo código cambia el esquema, después hay
+    /// que llamar a su `migrate`.
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<(), Error> {
+        let admin = Self::get_admin(env.clone())?;
+        admin.require_auth();
+        storage::extend_instance(&env);
+
+        log!(&env, "Actualizando el código del contrato");
+        env.deployer()
+            .update_current_contract_wasm(new_wasm_hash.clone());
+
+        events::upgraded(&env, &admin, &new_wasm_hash);
+        Ok(())
+    }
+
+    /// Pasar el almacenamiento al esquema actual y devolver cuántos votos se migraron
+    ///
+    /// Con datos del contrato original (versión 0) lo autoriza su creador, que pasa a ser
+    /// el administrador, y la votación se convierte en la propuesta 0 con opciones Si/No.
+    /// Como el contrato original no permitía recorrer los votantes, sus registros se
+    /// mueven en lotes indicando las direcciones en `voters`; los lotes siguientes los
+    /// autoriza el administrador. Los votos migrados quedan con opción `UNKNOWN_OPTION`.
+    pub fn migrate(env: Env, voters: Vec<Address>) -> Result<u32, Error> {
+        let from_version = Self::version(env.clone());
+        if from_version > SCHEMA_VERSION {
+            return Err(fail(Error::UnsupportedVersion));
+        }
+
+        if from_version == 0 {
+            let creator: Address = env
+                .storage()
+                .instance()
+                .get(&LegacyDataKey::Creator)
+                .ok_or_else(|| fail(Error::NotInitialized))?;
+            creator.require_auth();
+            storage::extend_instance(&env);
+
+            Self::_migrate_legacy_ballot(&env, &creator);
+        } else {
+            Self::get_admin(env.clone())?.require_auth();
+            storage::extend_instance(&env);
+        }
+
+        let mut migrated: u32 = 0;
+        for voter in voters.iter() {
+            let legacy_key = LegacyDataKey::HasVoted(voter.clone());
+            if !env.storage().instance().has(&legacy_key) {
+                continue;
+            }
+
+            env.storage().instance().remove(&legacy_key);
+            storage::set(
+                &env,
+                &DataKey::HasVoted(0, voter),
+                &VoteRecord {
+                    option: UNKNOWN_OPTION,
+                    weight: 1,
+                },
+            );
+            migrated += 1;
+        }
+
+        events::migrated(&env, from_version, SCHEMA_VERSION, migrated);
+        log!(
+            &env,
+            "Esquema migrado de la versión {} a la {}, {} votos movidos",
+            from_version,
+            SCHEMA_VERSION,
+            migrated
+        );
+        Ok(migrated)
+    }
+
+    /// Crear una nueva propuesta y devolver su id
+    pub fn create_proposal(
+        env: Env,
+        creator: Address,
+        config: ProposalConfig,
+    ) -> Result<u32, Error> {
+        // El creador debe autorizar
+        creator.require_auth();
+        storage::extend_instance(&env);
+
+        if config.title.len() > MAX_TITLE_LEN || config.description.len() > MAX_DESCRIPTION_LEN {
+            return Err(fail(Error::MetadataTooLong));
+        }
+
+        let options_len = config.options.len();
+        if !(2..=MAX_OPTIONS).contains(&options_len) {
+            return Err(fail(Error::InvalidOptions));
+        }
+
+        if let Some(end_time) = config.end_time {
+            if end_time <= config.start_time {
+                return Err(fail(Error::InvalidWindow));
+            }
+        }
+
+        // La votación secreta necesita una fase de compromiso cerrada y otra para revelar
+        if let Some(reveal_end_time) = config.reveal_end_time {
+            if config
+                .end_time
+                .is_none_or(|end_time| reveal_end_time <= end_time)
+            {
+                return Err(fail(Error::InvalidWindow));
+            }
+        }
+
+        if config.timelock > MAX_TIMELOCK {
+            return Err(fail(Error::InvalidWindow));
+        }
+
+        if config.quorum < 0 || !(1..=BPS_DENOMINATOR).contains(&config.threshold_bps) {
+            return Err(fail(Error::InvalidThreshold));
+        }
+
+        Self::_validate_committee(&config)?;
+
+        // Los créditos son de cada votante: ni se delegan ni se reparten en secreto. Y sin
+        // lista de votantes, cualquiera podría repartir su voto entre muchas direcciones
+        // para esquivar el coste cuadrático.
+        if config.credits > 0
+            && (!config.restricted
+                || config.ranked
+                || config.reveal_end_time.is_some()
+                || config.allow_vote_change)
+        {
+            return Err(fail(Error::QuadraticBallotMismatch));
         }
 
-        // Verificar que no haya votado antes
-        let has_voted_key = DataKey::HasVoted(voter.clone());
-        if env.storage().instance().has(&has_voted_key) {
-            panic!("Ya votaste");
+        // El recuento por ranking necesita cada voto en claro y fijo
+        if config.ranked {
+            if options_len > MAX_RANKED_OPTIONS {
+                return Err(fail(Error::InvalidOptions));
+            }
+            if config.reveal_end_time.is_some() || config.allow_vote_change {
+                return Err(fail(Error::RankedBallotMismatch));
+            }
         }
 
-        // Registrar que votó
-        env.storage().instance().set(&has_voted_key, &true);
+        let proposal_id: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::ProposalCount)
+            .ok_or_else(|| fail(Error::NotInitialized))?;
 
-        // Incrementar votos SI
-        let current_votes: u32 = env.storage().instance().get(&DataKey::VotesSi).unwrap_or(0);
+        log!(
+            &env,
+            "Creando propuesta {}, creador: {}",
+            proposal_id,
+            creator
+        );
 
+        // Guardar datos iniciales de la propuesta
+        storage::set(&env, &DataKey::Creator(proposal_id), &creator);
+        let state = if config.draft {
+            BallotState::Draft
+        } else {
+            BallotState::Open
+        };
+        storage::set(&env, &DataKey::State(proposal_id), &state);
+        for option_index in 0..options_len {
+            storage::set(&env, &DataKey::Votes(proposal_id, option_index), &0i128);
+        }
+        storage::set(&env, &DataKey::Config(proposal_id), &config);
         env.storage()
             .instance()
-            .set(&DataKey::VotesSi, &(current_votes + 1));
+            .set(&DataKey::ProposalCount, &(proposal_id + 1));
+
+        // Bloquear el depósito anti-spam, si lo hay
+        if let Some(fees) = env.storage().instance().get::<_, FeeConfig>(&DataKey::Fees) {
+            if fees.deposit > 0 {
+                token::Client::new(&env, &fees.token).transfer(
+                    &creator,
+                    &env.current_contract_address(),
+                    &fees.deposit,
+                );
+                storage::set(
+                    &env,
+                    &DataKey::Deposit(proposal_id),
+                    &Deposit {
+                        token: fees.token,
+                        amount: fees.deposit,
+                        treasury: fees.treasury,
+                    },
+                );
+            }
+        }
+
+        events::proposal_created(&env, proposal_id, &creator);
+        log!(&env, "Propuesta {} creada correctamente", proposal_id);
+        Ok(proposal_id)
+    }
+
+    /// Votar por una de las opciones de la propuesta
+    pub fn vote(
+        env: Env,
+        voter: Address,
+        proposal_id: u32,
+        option_index: u32,
+    ) -> Result<(), Error> {
+        Self::_vote(env, voter, proposal_id, option_index)
+    }
+
+    /// Votar SI (primera opción)
+    pub fn vote_si(env: Env, voter: Address, proposal_id: u32) -> Result<(), Error> {
+        Self::_vote(env, voter, proposal_id, 0)
+    }
+
+    /// Votar NO (segunda opción)
+    pub fn vote_no(env: Env, voter: Address, proposal_id: u32) -> Result<(), Error> {
+        Self::_vote(env, voter, proposal_id, 1)
+    }
+
+    /// Registrar la clave pública ed25519 con la que el votante firmará votos fuera de la
+    /// cadena para que los envíe un relayer
+    pub fn register_voter_key(
+        env: Env,
+        voter: Address,
+        public_key: BytesN<32>,
+    ) -> Result<(), Error> {
+        voter.require_auth();
+        storage::extend_instance(&env);
+
+        storage::set(&env, &DataKey::VoterKey(voter.clone()), &public_key);
+
+        events::voter_key_registered(&env, &voter, &public_key);
+        log!(&env, "Clave para votos firmados registrada por {}", voter);
+        Ok(())
+    }
+
+    /// Registrar un lote de votos firmados fuera de la cadena; `relayer` envía la
+    /// transacción y paga la tarifa por voto. Devuelve cuántos votos se registraron.
+    ///
+    /// Cada firma tiene que ser de la clave registrada del votante sobre
+    /// `vote_message(proposal_id, option, nonce)` y usar su siguiente nonce, así un voto
+    /// firmado no se puede repetir. El lote es atómico: si un voto falla no se registra
+    /// ninguno, y una firma inválida aborta la llamada.
+    pub fn relay_votes(
+        env: Env,
+        relayer: Address,
+        proposal_id: u32,
+        votes: Vec<SignedVote>,
+    ) -> Result<u32, Error> {
+        relayer.require_auth();
+        storage::extend_instance(&env);
+
+        if env.storage().instance().has(&DataKey::Token) {
+            return Err(fail(Error::RelayNotAllowed));
+        }
+
+        for vote in votes.iter() {
+            let public_key: BytesN<32> = storage::get(&env, &DataKey::VoterKey(vote.voter.clone()))
+                .ok_or_else(|| fail(Error::NoVoterKey))?;
+
+            let nonce_key = DataKey::Nonce(vote.voter.clone());
+            let nonce: u64 = storage::get(&env, &nonce_key).unwrap_or(0);
+            if vote.nonce != nonce {
+                return Err(fail(Error::InvalidNonce));
+            }
+            storage::set(&env, &nonce_key, &(nonce + 1));
+
+            let message = Self::vote_message(env.clone(), proposal_id, vote.option, vote.nonce);
+            env.crypto()
+                .ed25519_verify(&public_key, &message, &vote.signature);
+
+            Self::_cast_vote(&env, &vote.voter, proposal_id, vote.option, &relayer)?;
+        }
 
         log!(
             &env,
-            "Voto SI registrado. Total votos SI: {}",
-            current_votes + 1
+            "{} votos firmados registrados por {}",
+            votes.len(),
+            relayer
         );
+        Ok(votes.len())
     }
-    /// Votar NO
-    pub fn vote_no(env: Env, voter: Address) {
-        // El votante debe autorizar
+
+    /// Votar en una propuesta por ranking, con las opciones de más a menos preferida
+    ///
+    /// No hace falta ordenarlas todas; si se eliminan todas las del ranking, el voto deja
+    /// de contar en las rondas siguientes.
+    pub fn vote_ranked(
+        env: Env,
+        voter: Address,
+        proposal_id: u32,
+        ranking: Vec<u32>,
+    ) -> Result<(), Error> {
         voter.require_auth();
+        storage::extend_instance(&env);
 
-        log!(&env, "Usuario {} votando NO", voter);
+        let config = Self::_config(&env, proposal_id)?;
+        if !config.ranked {
+            return Err(fail(Error::RankedBallotMismatch));
+        }
 
-        // Verificar que la votación esté activa
-        let active: bool = env
-            .storage()
-            .instance()
-            .get(&DataKey::Active)
-            .unwrap_or(false);
+        // Sin opciones repetidas ni inexistentes
+        if ranking.is_empty() || ranking.len() > config.options.len() {
+            return Err(fail(Error::InvalidRanking));
+        }
+        for (position, option_index) in ranking.iter().enumerate() {
+            if option_index >= config.options.len()
+                || ranking
+                    .iter()
+                    .skip(position + 1)
+                    .any(|other| other == option_index)
+            {
+                return Err(fail(Error::InvalidRanking));
+            }
+        }
 
-        if !active {
-            panic!("La votación no está activa");
+        let weight = Self::_check_can_vote(&env, proposal_id, &config, &voter)?;
+
+        // Cada votante entra una sola vez en el recuento, aunque vuelva a votar
+        let ranking_key = DataKey::Ranking(proposal_id, voter.clone());
+        if !storage::has(&env, &ranking_key) {
+            let count_key = DataKey::RankedVoterCount(proposal_id);
+            let count: u32 = storage::get(&env, &count_key).unwrap_or(0);
+            storage::set(&env, &DataKey::RankedVoter(proposal_id, count), &voter);
+            storage::set(&env, &count_key, &(count + 1));
+        }
+        storage::set(&env, &ranking_key, &ranking);
+
+        // La primera preferencia es la que se ve en `get_results`
+        Self::_record_vote(
+            &env,
+            proposal_id,
+            &voter,
+            ranking.get_unchecked(0),
+            weight,
+            &voter,
+        );
+        Ok(())
+    }
+
+    /// Dar `votes` votos más a una opción de una propuesta cuadrática
+    ///
+    /// Se puede llamar varias veces y repartir los créditos entre opciones: tener n votos
+    /// en una opción cuesta n² créditos en total, así que pasar de 2 a 3 cuesta 5.
+    /// El reparto se ve con `get_quadratic_votes`; `get_vote` no aplica a estas propuestas.
+    pub fn vote_quadratic(
+        env: Env,
+        voter: Address,
+        proposal_id: u32,
+        option_index: u32,
+        votes: u32,
+    ) -> Result<(), Error> {
+        voter.require_auth();
+        storage::extend_instance(&env);
+
+        let config = Self::_config(&env, proposal_id)?;
+        if config.credits == 0 {
+            return Err(fail(Error::QuadraticBallotMismatch));
+        }
+        if !Self::_is_open(&env, proposal_id, &config) {
+            return Err(fail(Error::VotingNotActive));
+        }
+        if !Self::_is_eligible(&env, proposal_id, &config, &voter) {
+            return Err(fail(Error::NotEligible));
+        }
+        if option_index >= config.options.len() {
+            return Err(fail(Error::InvalidOption));
+        }
+        if votes == 0 {
+            return Err(fail(Error::NoVotingPower));
         }
 
-        // Verificar que no haya votado antes
-        let has_voted_key = DataKey::HasVoted(voter.clone());
-        if env.storage().instance().has(&has_voted_key) {
-            panic!("Ya votaste");
+        let votes_key = DataKey::QuadraticVotes(proposal_id, voter.clone(), option_index);
+        let previous: u32 = storage::get(&env, &votes_key).unwrap_or(0);
+        let total = previous as i128 + votes as i128;
+        let cost = total * total - previous as i128 * previous as i128;
+
+        let spent_key = DataKey::CreditsSpent(proposal_id, voter.clone());
+        let spent: i128 = storage::get(&env, &spent_key).unwrap_or(0);
+        if spent + cost > config.credits as i128 {
+            return Err(fail(Error::InsufficientCredits));
         }
 
-        // Registrar que votó
-        env.storage().instance().set(&has_voted_key, &true);
+        storage::set(&env, &votes_key, &(total as u32));
+        storage::set(&env, &spent_key, &(spent + cost));
+
+        // Sin `VoteRecord`: un voto cuadrático no es una sola opción con un peso
+        Self::_count_vote(
+            &env,
+            proposal_id,
+            &voter,
+            option_index,
+            votes as i128,
+            &voter,
+        );
+        Ok(())
+    }
 
-        // Incrementar votos NO
-        let current_votes: u32 = env.storage().instance().get(&DataKey::VotesNo).unwrap_or(0);
+    /// Cerrar votación (el creador de la propuesta, el administrador o un `Role::Closer`)
+    ///
+    /// Si la propuesta tiene comité, se cierra con `approve_close`.
+    pub fn close_voting(env: Env, caller: Address, proposal_id: u32) -> Result<(), Error> {
+        caller.require_auth();
+        storage::extend_instance(&env);
 
-        env.storage()
-            .instance()
-            .set(&DataKey::VotesNo, &(current_votes + 1));
+        log!(&env, "Cerrando votación de la propuesta {}...", proposal_id);
+
+        // Verificar que tenga permiso para cerrarla
+        Self::_require_authorized(&env, proposal_id, &caller, Role::Closer)?;
+        if !Self::_config(&env, proposal_id)?.committee.is_empty() {
+            return Err(fail(Error::CommitteeRequired));
+        }
+
+        Self::_close(&env, proposal_id)
+    }
+
+    /// Seguir con el recuento por ranking de una propuesta cerrada, contando hasta `limit`
+    /// votos (como mucho `MAX_PAGE_SIZE`). Devuelve el resultado, o `Pending` si quedan
+    /// votos por contar. Cualquiera puede llamarla.
+    pub fn tally_ranked(env: Env, proposal_id: u32, limit: u32) -> Result<Outcome, Error> {
+        storage::extend_instance(&env);
+
+        let config = Self::_config(&env, proposal_id)?;
+        if !storage::has(&env, &DataKey::IrvProgress(proposal_id)) {
+            return Err(fail(Error::NoPendingTally));
+        }
+
+        let outcome = Self::_irv_step(&env, proposal_id, &config, limit);
+        if outcome != Outcome::Pending {
+            Self::_finish_close(&env, proposal_id, &outcome);
+        }
+        Ok(outcome)
+    }
+
+    /// Aprobar el cierre de una propuesta como firmante de su comité
+    ///
+    /// La aprobación que alcanza el umbral cierra la votación.
+    pub fn approve_close(env: Env, signer: Address, proposal_id: u32) -> Result<(), Error> {
+        signer.require_auth();
+        storage::extend_instance(&env);
 
+        let config = Self::_config(&env, proposal_id)?;
+        if config.committee.is_empty() {
+            return Err(fail(Error::NoCommittee));
+        }
+        if !config.committee.contains(&signer) {
+            return Err(fail(Error::Unauthorized));
+        }
+        if !matches!(
+            Self::_state(&env, proposal_id),
+            BallotState::Open | BallotState::Paused
+        ) {
+            return Err(fail(Error::InvalidTransition));
+        }
+
+        let key = DataKey::CloseApprovals(proposal_id);
+        let mut approvals: Vec<Address> = storage::get(&env, &key).unwrap_or(Vec::new(&env));
+        if approvals.contains(&signer) {
+            return Err(fail(Error::AlreadyApproved));
+        }
+        approvals.push_back(signer.clone());
+        storage::set(&env, &key, &approvals);
+
+        events::close_approved(&env, proposal_id, &signer, approvals.len());
         log!(
             &env,
-            "Voto NO registrado. Total votos NO: {}",
-            current_votes + 1
+            "{} aprueba cerrar la propuesta {} ({}/{})",
+            signer,
+            proposal_id,
+            approvals.len(),
+            config.committee_threshold
         );
+
+        if approvals.len() >= config.committee_threshold {
+            Self::_close(&env, proposal_id)?;
+        }
+        Ok(())
     }
-    /// Cerrar votación (solo el creador)
-    pub fn close_voting(env: Env, creator: Address) {
-        creator.require_auth();
 
-        log!(&env, "Cerrando votación...");
+    /// Abrir una propuesta creada en `Draft`
+    /// (el creador, el administrador o un `Role::Moderator`)
+    pub fn open_proposal(env: Env, caller: Address, proposal_id: u32) -> Result<(), Error> {
+        caller.require_auth();
+        storage::extend_instance(&env);
+        Self::_require_authorized(&env, proposal_id, &caller, Role::Moderator)?;
 
-        // Verificar que sea el creador
-        let stored_creator: Address = env
-            .storage()
-            .instance()
-            .get(&DataKey::Creator)
-            .expect("No hay creador registrado");
+        Self::_transition(&env, proposal_id, &[BallotState::Draft], BallotState::Open)
+    }
+
+    /// Pausar una propuesta abierta (el creador, el administrador o un `Role::Moderator`)
+    pub fn pause(env: Env, caller: Address, proposal_id: u32) -> Result<(), Error> {
+        caller.require_auth();
+        storage::extend_instance(&env);
+        Self::_require_authorized(&env, proposal_id, &caller, Role::Moderator)?;
+        Self::_require_before_deadline(&env, proposal_id)?;
+
+        Self::_transition(&env, proposal_id, &[BallotState::Open], BallotState::Paused)
+    }
+
+    /// Reanudar una propuesta pausada (el creador, el administrador o un `Role::Moderator`)
+    pub fn resume(env: Env, caller: Address, proposal_id: u32) -> Result<(), Error> {
+        caller.require_auth();
+        storage::extend_instance(&env);
+        Self::_require_authorized(&env, proposal_id, &caller, Role::Moderator)?;
+        Self::_require_before_deadline(&env, proposal_id)?;
 
-        if stored_creator != creator {
-            panic!("Solo el creador puede cerrar la votación");
+        Self::_transition(&env, proposal_id, &[BallotState::Paused], BallotState::Open)
+    }
+
+    /// Reabrir una propuesta cerrada antes de que termine su ventana; descarta el resultado
+    /// (el creador, el administrador o un `Role::Closer`)
+    pub fn reopen(env: Env, caller: Address, proposal_id: u32) -> Result<(), Error> {
+        caller.require_auth();
+        storage::extend_instance(&env);
+        Self::_require_authorized(&env, proposal_id, &caller, Role::Closer)?;
+
+        // Una propuesta ya en cola, vetada o ejecutada no se puede volver a votar, ni una
+        // en la que alguien liberó sus tokens: podría moverlos y votar otra vez con ellos
+        let config = Self::_config(&env, proposal_id)?;
+        if Self::_deadline_passed(&env, &config)
+            || storage::has(&env, &DataKey::Queue(proposal_id))
+            || storage::has(&env, &DataKey::Executed(proposal_id))
+            || storage::has(&env, &DataKey::Unlocked(proposal_id))
+        {
+            return Err(fail(Error::InvalidTransition));
         }
 
-        // Cerrar votación
-        env.storage().instance().set(&DataKey::Active, &false);
+        Self::_transition(&env, proposal_id, &[BallotState::Closed], BallotState::Open)?;
+        storage::remove(&env, &DataKey::Outcome(proposal_id));
+        storage::remove(&env, &DataKey::IrvProgress(proposal_id));
+        storage::remove(&env, &DataKey::IrvTally(proposal_id));
+        // Para volver a cerrar, el comité tiene que aprobar de nuevo
+        storage::remove(&env, &DataKey::CloseApprovals(proposal_id));
+        Ok(())
+    }
+
+    /// Cancelar una propuesta que no esté cerrada ni haya terminado su ventana
+    /// (el creador, el administrador o un `Role::Closer`)
+    pub fn cancel(env: Env, caller: Address, proposal_id: u32) -> Result<(), Error> {
+        caller.require_auth();
+        storage::extend_instance(&env);
+        Self::_require_authorized(&env, proposal_id, &caller, Role::Closer)?;
+        Self::_require_before_deadline(&env, proposal_id)?;
 
-        log!(&env, "Votación cerrada");
+        Self::_transition(
+            &env,
+            proposal_id,
+            &[BallotState::Draft, BallotState::Open, BallotState::Paused],
+            BallotState::Cancelled,
+        )?;
+        storage::set(&env, &DataKey::Outcome(proposal_id), &Outcome::Cancelled);
+        Self::_settle_deposit(&env, proposal_id, &Outcome::Cancelled);
+        Ok(())
     }
-    /// Ver resultados
-    pub fn get_results(env: Env) -> (u32, u32, bool) {
-        let votes_si: u32 = env.storage().instance().get(&DataKey::VotesSi).unwrap_or(0);
 
-        let votes_no: u32 = env.storage().instance().get(&DataKey::VotesNo).unwrap_or(0);
+    /// Poner en cola las acciones de una propuesta aprobada; se podrán ejecutar cuando pase
+    /// su `timelock`, o la espera mínima del contrato si es mayor. Cualquiera puede
+    /// llamarla.
+    pub fn queue(env: Env, proposal_id: u32) -> Result<u64, Error> {
+        storage::extend_instance(&env);
 
-        let active: bool = env
-            .storage()
-            .instance()
-            .get(&DataKey::Active)
-            .unwrap_or(false);
+        let config = Self::_config(&env, proposal_id)?;
+        Self::_require_passed(&env, proposal_id, &config)?;
+
+        let queue_key = DataKey::Queue(proposal_id);
+        if storage::has(&env, &queue_key) || storage::has(&env, &DataKey::Executed(proposal_id)) {
+            return Err(fail(Error::AlreadyQueued));
+        }
+
+        let delay = config.timelock.max(Self::get_min_timelock(env.clone()));
+        let eta = env.ledger().timestamp().saturating_add(delay);
+        storage::set(&env, &queue_key, &QueueState::Queued(eta));
+
+        events::queued(&env, proposal_id, eta);
+        log!(
+            &env,
+            "Acciones de la propuesta {} en cola hasta {}",
+            proposal_id,
+            eta
+        );
+        Ok(eta)
+    }
+
+    /// Vetar las acciones en cola de una propuesta (un `Role::Guardian` o el administrador)
+    pub fn cancel_queued(env: Env, guardian: Address, proposal_id: u32) -> Result<(), Error> {
+        guardian.require_auth();
+        storage::extend_instance(&env);
+
+        if !Self::_is_admin(&env, &guardian)
+            && !storage::has(&env, &DataKey::Role(guardian.clone(), Role::Guardian))
+        {
+            return Err(fail(Error::Unauthorized));
+        }
+
+        let queue_key = DataKey::Queue(proposal_id);
+        match storage::get(&env, &queue_key) {
+            Some(QueueState::Queued(_)) => {}
+            Some(QueueState::Vetoed) => return Err(fail(Error::Vetoed)),
+            _ => return Err(fail(Error::NotQueued)),
+        }
+        storage::set(&env, &queue_key, &QueueState::Vetoed);
+
+        events::vetoed(&env, proposal_id, &guardian);
+        log!(
+            &env,
+            "{} vetó las acciones de la propuesta {}",
+            guardian,
+            proposal_id
+        );
+        Ok(())
+    }
+
+    /// Ejecutar las acciones de una propuesta cerrada en la que ganó la primera opción
+    ///
+    /// Antes hay que ponerlas en cola con `queue` y esperar a que pase el timelock.
+    /// Cualquiera puede llamarla, pero solo una vez por propuesta.
+    pub fn execute(env: Env, proposal_id: u32) -> Result<(), Error> {
+        storage::extend_instance(&env);
+
+        let config = Self::_config(&env, proposal_id)?;
+        Self::_require_passed(&env, proposal_id, &config)?;
+
+        // Marcarla antes de llamar, por si alguna acción vuelve a entrar en este contrato
+        let executed_key = DataKey::Executed(proposal_id);
+        if storage::has(&env, &executed_key) {
+            return Err(fail(Error::AlreadyExecuted));
+        }
+
+        match storage::get(&env, &DataKey::Queue(proposal_id)) {
+            Some(QueueState::Queued(eta)) if env.ledger().timestamp() >= eta => {}
+            Some(QueueState::Queued(_)) => return Err(fail(Error::TimelockNotExpired)),
+            Some(QueueState::Vetoed) => return Err(fail(Error::Vetoed)),
+            _ => return Err(fail(Error::NotQueued)),
+        }
+        storage::set(&env, &executed_key, &true);
+
+        for action in config.actions.iter() {
+            log!(
+                &env,
+                "Propuesta {}: llamando a {} en {}",
+                proposal_id,
+                action.function,
+                action.contract
+            );
+            env.invoke_contract::<Val>(&action.contract, &action.function, action.args);
+        }
+
+        storage::remove(&env, &DataKey::Queue(proposal_id));
+
+        events::executed(&env, proposal_id);
+        Ok(())
+    }
+
+    /// Liberar los tokens bloqueados al votar, una vez terminada la votación. Los tokens
+    /// se devuelven cuando ya no los usa ninguna otra propuesta abierta; devuelve la
+    /// cantidad transferida (0 si otras propuestas siguen usándolos).
+    pub fn unlock_tokens(env: Env, voter: Address, proposal_id: u32) -> Result<i128, Error> {
+        voter.require_auth();
+        storage::extend_instance(&env);
+
+        let config = Self::_config(&env, proposal_id)?;
+        if !Self::_is_finished(&env, proposal_id, &config) {
+            return Err(fail(Error::VotingStillOpen));
+        }
+
+        let locked_key = DataKey::Locked(proposal_id, voter.clone());
+        if !storage::has(&env, &locked_key) {
+            return Err(fail(Error::NothingLocked));
+        }
+
+        // Si hay tokens bloqueados, el token está configurado
+        let token: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Token)
+            .ok_or_else(|| fail(Error::NotInitialized))?;
+
+        storage::remove(&env, &locked_key);
+        storage::set(&env, &DataKey::Unlocked(proposal_id), &true);
+
+        let escrow_key = DataKey::Escrow(voter.clone());
+        let mut escrow: Escrow =
+            storage::get(&env, &escrow_key).ok_or_else(|| fail(Error::NothingLocked))?;
+        escrow.proposals -= 1;
+        if escrow.proposals > 0 {
+            storage::set(&env, &escrow_key, &escrow);
+            log!(
+                &env,
+                "Los tokens de {} siguen bloqueados por {} propuestas",
+                voter,
+                escrow.proposals
+            );
+            return Ok(0);
+        }
+
+        storage::remove(&env, &escrow_key);
+        token::Client::new(&env, &token).transfer(
+            &env.current_contract_address(),
+            &voter,
+            &escrow.amount,
+        );
+
+        events::tokens_unlocked(&env, proposal_id, &voter, escrow.amount);
+        log!(&env, "Devueltos {} tokens a {}", escrow.amount, voter);
+        Ok(escrow.amount)
+    }
+
+    /// Añadir una dirección a la lista de votantes
+    /// (el creador, el administrador o un `Role::Moderator`)
+    pub fn add_voter(
+        env: Env,
+        caller: Address,
+        proposal_id: u32,
+        voter: Address,
+    ) -> Result<(), Error> {
+        Self::add_voters(
+            env.clone(),
+            caller,
+            proposal_id,
+            Vec::from_array(&env, [voter]),
+        )
+    }
+
+    /// Añadir varias direcciones a la lista de votantes de una vez
+    /// (el creador, el administrador o un `Role::Moderator`)
+    pub fn add_voters(
+        env: Env,
+        caller: Address,
+        proposal_id: u32,
+        voters: Vec<Address>,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+        storage::extend_instance(&env);
+        Self::_require_authorized(&env, proposal_id, &caller, Role::Moderator)?;
+
+        for voter in voters.iter() {
+            storage::set(&env, &DataKey::Eligible(proposal_id, voter), &true);
+        }
+
+        events::voters_added(&env, proposal_id, &voters);
+        log!(
+            &env,
+            "{} votantes añadidos a la propuesta {}",
+            voters.len(),
+            proposal_id
+        );
+        Ok(())
+    }
+
+    /// Quitar una dirección de la lista de votantes
+    /// (el creador, el administrador o un `Role::Moderator`)
+    pub fn remove_voter(
+        env: Env,
+        caller: Address,
+        proposal_id: u32,
+        voter: Address,
+    ) -> Result<(), Error> {
+        caller.require_auth();
+        storage::extend_instance(&env);
+        Self::_require_authorized(&env, proposal_id, &caller, Role::Moderator)?;
+
+        storage::remove(&env, &DataKey::Eligible(proposal_id, voter.clone()));
+
+        events::voter_removed(&env, proposal_id, &voter);
+        log!(
+            &env,
+            "Votante {} quitado de la propuesta {}",
+            voter,
+            proposal_id
+        );
+        Ok(())
+    }
+
+    /// Cambiar el voto a otra opción, si la propuesta lo permite
+    pub fn change_vote(
+        env: Env,
+        voter: Address,
+        proposal_id: u32,
+        option_index: u32,
+    ) -> Result<(), Error> {
+        voter.require_auth();
+        storage::extend_instance(&env);
+
+        let config = Self::_config(&env, proposal_id)?;
+        let record = Self::_existing_vote(&env, proposal_id, &config, &voter)?;
+
+        if option_index >= config.options.len() {
+            return Err(fail(Error::InvalidOption));
+        }
+
+        Self::_add_votes(&env, proposal_id, &voter, record.option, -record.weight);
+        Self::_add_votes(&env, proposal_id, &voter, option_index, record.weight);
+
+        storage::set(
+            &env,
+            &DataKey::HasVoted(proposal_id, voter.clone()),
+            &VoteRecord {
+                option: option_index,
+                weight: record.weight,
+            },
+        );
+
+        events::vote_changed(
+            &env,
+            proposal_id,
+            &voter,
+            record.option,
+            option_index,
+            record.weight,
+        );
+        log!(
+            &env,
+            "Usuario {} cambia su voto de la opción {} a la {}",
+            voter,
+            record.option,
+            option_index
+        );
+        Ok(())
+    }
+
+    /// Retirar el voto, si la propuesta lo permite
+    pub fn retract_vote(env: Env, voter: Address, proposal_id: u32) -> Result<(), Error> {
+        voter.require_auth();
+        storage::extend_instance(&env);
+
+        let config = Self::_config(&env, proposal_id)?;
+        let record = Self::_existing_vote(&env, proposal_id, &config, &voter)?;
+
+        Self::_add_votes(&env, proposal_id, &voter, record.option, -record.weight);
+        storage::remove(&env, &DataKey::HasVoted(proposal_id, voter.clone()));
+
+        events::vote_retracted(&env, proposal_id, &voter, record.option, record.weight);
+        log!(&env, "Usuario {} retira su voto", voter);
+        Ok(())
+    }
+
+    /// Enviar el compromiso de un voto secreto: `sha256(opción || sal)`, con la opción
+    /// como u32 big-endian y una sal de 32 bytes que se mantiene en secreto hasta revelar
+    pub fn commit(
+        env: Env,
+        voter: Address,
+        proposal_id: u32,
+        commitment: BytesN<32>,
+    ) -> Result<(), Error> {
+        voter.require_auth();
+        storage::extend_instance(&env);
+
+        let config = Self::_config(&env, proposal_id)?;
+        if config.reveal_end_time.is_none() {
+            return Err(fail(Error::SecretBallotMismatch));
+        }
+        if !Self::_is_open(&env, proposal_id, &config) {
+            return Err(fail(Error::VotingNotActive));
+        }
+        if !Self::_is_eligible(&env, proposal_id, &config, &voter) {
+            return Err(fail(Error::NotEligible));
+        }
+
+        let commitment_key = DataKey::Commitment(proposal_id, voter.clone());
+        if storage::has(&env, &commitment_key) {
+            return Err(fail(Error::AlreadyVoted));
+        }
+
+        Self::_lock_weight(&env, proposal_id, &voter)?;
+        storage::set(&env, &commitment_key, &commitment);
+
+        events::committed(&env, proposal_id, &voter);
+        log!(&env, "Compromiso de {} registrado", voter);
+        Ok(())
+    }
+
+    /// Revelar un voto secreto; solo cuentan los compromisos revelados a tiempo
+    pub fn reveal(
+        env: Env,
+        voter: Address,
+        proposal_id: u32,
+        option_index: u32,
+        salt: BytesN<32>,
+    ) -> Result<(), Error> {
+        voter.require_auth();
+        storage::extend_instance(&env);
+
+        let config = Self::_config(&env, proposal_id)?;
+        let Some(reveal_end_time) = config.reveal_end_time else {
+            return Err(fail(Error::SecretBallotMismatch));
+        };
+
+        let open = Self::_state(&env, proposal_id) == BallotState::Open;
+        let now = env.ledger().timestamp();
+        let reveal_started = config.end_time.is_some_and(|end_time| now >= end_time);
+        if !open || !reveal_started || now >= reveal_end_time {
+            return Err(fail(Error::RevealNotActive));
+        }
+
+        let commitment_key = DataKey::Commitment(proposal_id, voter.clone());
+        let commitment: BytesN<32> =
+            storage::get(&env, &commitment_key).ok_or_else(|| fail(Error::NotVoted))?;
+
+        let mut preimage = Bytes::from_array(&env, &option_index.to_be_bytes());
+        preimage.append(&salt.into());
+        if env.crypto().sha256(&preimage).to_bytes() != commitment {
+            return Err(fail(Error::InvalidReveal));
+        }
+
+        if option_index >= config.options.len() {
+            return Err(fail(Error::InvalidOption));
+        }
+
+        storage::remove(&env, &commitment_key);
+        let weight = Self::_own_weight(&env, proposal_id, &voter);
+        Self::_record_vote(&env, proposal_id, &voter, option_index, weight, &voter);
+        Ok(())
+    }
+
+    /// Delegar el voto en otra dirección: cuenta para lo que vote `to`
+    /// (o en quien `to` delegue a su vez)
+    pub fn delegate(env: Env, from: Address, proposal_id: u32, to: Address) -> Result<(), Error> {
+        from.require_auth();
+        storage::extend_instance(&env);
+
+        let config = Self::_config(&env, proposal_id)?;
+        if config.reveal_end_time.is_some() {
+            return Err(fail(Error::SecretBallotMismatch));
+        }
+        if config.credits > 0 {
+            return Err(fail(Error::QuadraticBallotMismatch));
+        }
+        if !Self::_is_open(&env, proposal_id, &config) {
+            return Err(fail(Error::VotingNotActive));
+        }
+
+        if !Self::_is_eligible(&env, proposal_id, &config, &from) {
+            return Err(fail(Error::NotEligible));
+        }
+
+        if Self::_has_voted(&env, proposal_id, &from) {
+            return Err(fail(Error::AlreadyVoted));
+        }
+
+        let delegate_key = DataKey::Delegate(proposal_id, from.clone());
+        if storage::has(&env, &delegate_key) {
+            return Err(fail(Error::AlreadyDelegated));
+        }
+
+        // Seguir la cadena desde `to` para detectar ciclos y votos ya emitidos. La cadena
+        // completa son las delegaciones que ya llegan a `from`, la nueva y las que siguen
+        // desde `to`.
+        let height: u32 =
+            storage::get(&env, &DataKey::DelegationHeight(proposal_id, from.clone())).unwrap_or(0);
+        let mut path = Vec::new(&env);
+        let mut current = to.clone();
+        loop {
+            if current == from {
+                return Err(fail(Error::DelegationCycle));
+            }
+            if Self::_has_voted(&env, proposal_id, &current) {
+                return Err(fail(Error::DelegateAlreadyVoted));
+            }
+            path.push_back(current.clone());
+            if height + path.len() > MAX_DELEGATION_DEPTH {
+                return Err(fail(Error::DelegationTooDeep));
+            }
+            match storage::get(&env, &DataKey::Delegate(proposal_id, current.clone())) {
+                Some(next) => current = next,
+                None => break,
+            }
+        }
+
+        let weight = Self::_lock_weight(&env, proposal_id, &from)?
+            + Self::_delegated_weight(&env, proposal_id, &from);
+
+        storage::set(&env, &delegate_key, &to);
+        Self::_update_delegation_path(&env, proposal_id, &from, weight);
+        for (hops, node) in path.iter().enumerate() {
+            let height_key = DataKey::DelegationHeight(proposal_id, node);
+            let node_height: u32 = storage::get(&env, &height_key).unwrap_or(0);
+            storage::set(
+                &env,
+                &height_key,
+                &node_height.max(height + hops as u32 + 1),
+            );
+        }
+
+        events::delegated(&env, proposal_id, &from, &to);
+        log!(&env, "{} delega su voto en {}", from, to);
+        Ok(())
+    }
+
+    /// Revocar la delegación, siempre que el final de la cadena no haya votado todavía
+    pub fn revoke_delegation(env: Env, from: Address, proposal_id: u32) -> Result<(), Error> {
+        from.require_auth();
+        storage::extend_instance(&env);
+
+        let delegate_key = DataKey::Delegate(proposal_id, from.clone());
+        let to: Address =
+            storage::get(&env, &delegate_key).ok_or_else(|| fail(Error::NotDelegated))?;
+
+        if Self::_has_voted(
+            &env,
+            proposal_id,
+            &Self::_resolve_delegate(&env, proposal_id, &from),
+        ) {
+            return Err(fail(Error::DelegateAlreadyVoted));
+        }
+
+        // Las alturas no se recalculan: siguen siendo una cota de la cadena más larga
+        let weight = Self::_own_weight(&env, proposal_id, &from)
+            + Self::_delegated_weight(&env, proposal_id, &from);
+        Self::_update_delegation_path(&env, proposal_id, &from, -weight);
+        storage::remove(&env, &delegate_key);
+
+        events::delegation_revoked(&env, proposal_id, &from, &to);
+        log!(&env, "{} revoca su delegación en {}", from, to);
+        Ok(())
+    }
+
+    /// Extender el TTL de la instancia, de los datos de una propuesta y de los registros
+    /// de los votantes indicados, para que no se archiven a mitad de la votación.
+    /// Cualquiera puede llamarla.
+    pub fn bump(env: Env, proposal_id: u32, voters: Vec<Address>) -> Result<(), Error> {
+        let config = Self::_config(&env, proposal_id)?;
+
+        storage::extend_instance(&env);
+        storage::extend(&env, &DataKey::Creator(proposal_id));
+        storage::extend(&env, &DataKey::State(proposal_id));
+        storage::extend(&env, &DataKey::Outcome(proposal_id));
+        storage::extend(&env, &DataKey::CloseApprovals(proposal_id));
+        storage::extend(&env, &DataKey::Deposit(proposal_id));
+        storage::extend(&env, &DataKey::Executed(proposal_id));
+        storage::extend(&env, &DataKey::Queue(proposal_id));
+        storage::extend(&env, &DataKey::Unlocked(proposal_id));
+        for option_index in 0..config.options.len() {
+            storage::extend(&env, &DataKey::Votes(proposal_id, option_index));
+        }
+
+        storage::extend(&env, &DataKey::RankedVoterCount(proposal_id));
+        storage::extend(&env, &DataKey::IrvProgress(proposal_id));
+        storage::extend(&env, &DataKey::IrvTally(proposal_id));
+        storage::extend(&env, &DataKey::VoterLogLen(proposal_id));
+
+        for voter in voters.iter() {
+            storage::extend(&env, &DataKey::HasVoted(proposal_id, voter.clone()));
+            storage::extend(&env, &DataKey::Ranking(proposal_id, voter.clone()));
+            storage::extend(&env, &DataKey::CreditsSpent(proposal_id, voter.clone()));
+            for option_index in 0..config.options.len() {
+                storage::extend(
+                    &env,
+                    &DataKey::QuadraticVotes(proposal_id, voter.clone(), option_index),
+                );
+            }
+            storage::extend(&env, &DataKey::Locked(proposal_id, voter.clone()));
+            storage::extend(&env, &DataKey::Escrow(voter.clone()));
+            storage::extend(&env, &DataKey::Eligible(proposal_id, voter.clone()));
+            storage::extend(&env, &DataKey::Delegate(proposal_id, voter.clone()));
+            storage::extend(&env, &DataKey::DelegatedWeight(proposal_id, voter.clone()));
+            storage::extend(&env, &DataKey::DelegationHeight(proposal_id, voter.clone()));
+            storage::extend(&env, &DataKey::Commitment(proposal_id, voter));
+        }
+        Ok(())
+    }
+
+    /// Extender el TTL de los votantes de una propuesta por ranking desde la posición
+    /// `start`, como mucho `limit` (y nunca más de `MAX_PAGE_SIZE`), junto con sus
+    /// rankings, para que sigan ahí cuando se haga el recuento. Cualquiera puede llamarla.
+    pub fn bump_ranked_voters(
+        env: Env,
+        proposal_id: u32,
+        start: u32,
+        limit: u32,
+    ) -> Result<(), Error> {
+        let config = Self::_config(&env, proposal_id)?;
+        if !config.ranked {
+            return Err(fail(Error::RankedBallotMismatch));
+        }
+
+        storage::extend_instance(&env);
+        let count: u32 = storage::get(&env, &DataKey::RankedVoterCount(proposal_id)).unwrap_or(0);
+        let end = start.saturating_add(limit.min(MAX_PAGE_SIZE)).min(count);
+        for index in start..end {
+            // `get` ya extiende la entrada del índice
+            if let Some(voter) =
+                storage::get::<Address>(&env, &DataKey::RankedVoter(proposal_id, index))
+            {
+                storage::extend(&env, &DataKey::Ranking(proposal_id, voter.clone()));
+                storage::extend(&env, &DataKey::HasVoted(proposal_id, voter));
+            }
+        }
+        Ok(())
+    }
+
+    /// Extender el TTL del registro de votos de una propuesta desde la posición `start`,
+    /// como mucho `limit` entradas (y nunca más de `MAX_PAGE_SIZE`). Cualquiera puede
+    /// llamarla.
+    pub fn bump_voter_log(env: Env, proposal_id: u32, start: u32, limit: u32) -> Result<(), Error> {
+        let len = Self::voter_count(env.clone(), proposal_id)?;
+        let end = start.saturating_add(limit.min(MAX_PAGE_SIZE)).min(len);
+
+        storage::extend_instance(&env);
+        for index in start..end {
+            storage::extend(&env, &DataKey::VoterLog(proposal_id, index));
+        }
+        Ok(())
+    }
+
+    // --- Funciones privadas de ayuda ---
+
+    /// Cerrar la votación y fijar el resultado
+    fn _close(env: &Env, proposal_id: u32) -> Result<(), Error> {
+        Self::_transition(
+            env,
+            proposal_id,
+            &[BallotState::Open, BallotState::Paused],
+            BallotState::Closed,
+        )?;
+
+        let mut outcome = Self::_compute_outcome(env, proposal_id)?;
+
+        // El recuento por ranking empieza al cerrar y, si no cabe en una página, sigue
+        // con `tally_ranked`
+        if outcome == Outcome::Pending {
+            let config = Self::_config(env, proposal_id)?;
+            let options_len = config.options.len();
+            let mut counts = Vec::new(env);
+            let mut eliminated = Vec::new(env);
+            for _ in 0..options_len {
+                counts.push_back(0i128);
+                eliminated.push_back(false);
+            }
+            storage::set(
+                env,
+                &DataKey::IrvProgress(proposal_id),
+                &IrvProgress {
+                    next: 0,
+                    counts,
+                    eliminated,
+                    rounds: Vec::new(env),
+                },
+            );
+
+            outcome = Self::_irv_step(env, proposal_id, &config, MAX_PAGE_SIZE);
+            if outcome == Outcome::Pending {
+                log!(env, "Votación cerrada, recuento por ranking pendiente");
+                return Ok(());
+            }
+        }
+
+        Self::_finish_close(env, proposal_id, &outcome);
+        Ok(())
+    }
+
+    /// Guardar el resultado de una propuesta cerrada y resolver su depósito
+    fn _finish_close(env: &Env, proposal_id: u32, outcome: &Outcome) {
+        storage::set(env, &DataKey::Outcome(proposal_id), outcome);
+        Self::_settle_deposit(env, proposal_id, outcome);
+
+        events::closed(env, proposal_id, outcome);
+        log!(env, "Votación cerrada con resultado {:?}", outcome.clone());
+    }
+
+    /// Contar hasta `limit` votos del recuento por ranking en curso, pasando de ronda
+    /// cuando se acaban. Devuelve `Pending` mientras queden votos por contar.
+    fn _irv_step(env: &Env, proposal_id: u32, config: &ProposalConfig, limit: u32) -> Outcome {
+        let progress_key = DataKey::IrvProgress(proposal_id);
+        let Some(mut progress) = storage::get::<IrvProgress>(env, &progress_key) else {
+            return Outcome::Pending;
+        };
+        let voters: u32 = storage::get(env, &DataKey::RankedVoterCount(proposal_id)).unwrap_or(0);
+
+        let mut budget = limit.min(MAX_PAGE_SIZE);
+        loop {
+            let end = voters.min(progress.next + budget);
+            Self::_irv_count(
+                env,
+                proposal_id,
+                &progress.eliminated,
+                progress.next,
+                end,
+                &mut progress.counts,
+            );
+            budget -= end - progress.next;
+            progress.next = end;
+            if progress.next < voters {
+                storage::set(env, &progress_key, &progress);
+                return Outcome::Pending;
+            }
+
+            // Ronda completa
+            progress.rounds.push_back(progress.counts.clone());
+            if let Some(winner) = Self::_irv_next_round(
+                &progress.counts,
+                &mut progress.eliminated,
+                progress.rounds.len() >= config.options.len(),
+            ) {
+                storage::remove(env, &progress_key);
+                storage::set(
+                    env,
+                    &DataKey::IrvTally(proposal_id),
+                    &IrvTally {
+                        winner,
+                        rounds: progress.rounds,
+                    },
+                );
+                return match winner {
+                    Some(winner) => Outcome::Passed(winner),
+                    None => Outcome::Rejected,
+                };
+            }
+
+            progress.next = 0;
+            for option_index in 0..progress.counts.len() {
+                progress.counts.set(option_index, 0);
+            }
+            if budget == 0 {
+                storage::set(env, &progress_key, &progress);
+                return Outcome::Pending;
+            }
+        }
+    }
+
+    /// Sumar a `counts` los votos de las posiciones `start..end`: cada uno cuenta para su
+    /// opción preferida que no esté eliminada
+    fn _irv_count(
+        env: &Env,
+        proposal_id: u32,
+        eliminated: &Vec<bool>,
+        start: u32,
+        end: u32,
+        counts: &mut Vec<i128>,
+    ) {
+        for index in start..end {
+            let Some(voter) =
+                storage::get::<Address>(env, &DataKey::RankedVoter(proposal_id, index))
+            else {
+                continue;
+            };
+            let ranking: Option<Vec<u32>> =
+                storage::get(env, &DataKey::Ranking(proposal_id, voter.clone()));
+            let record: Option<VoteRecord> =
+                storage::get(env, &DataKey::HasVoted(proposal_id, voter));
+            let (Some(ranking), Some(record)) = (ranking, record) else {
+                continue;
+            };
+            if let Some(option_index) = ranking
+                .iter()
+                .find(|option_index| !eliminated.get_unchecked(*option_index))
+            {
+                counts.set(
+                    option_index,
+                    counts.get_unchecked(option_index) + record.weight,
+                );
+            }
+        }
+    }
+
+    /// Cerrar una ronda del recuento por ranking. Devuelve el ganador (o `None` si no hay
+    /// votos o quedan empatadas) cuando el recuento termina; si no, elimina las menos
+    /// votadas (todas las empatadas) para la siguiente ronda.
+    fn _irv_next_round(
+        counts: &Vec<i128>,
+        eliminated: &mut Vec<bool>,
+        last_round: bool,
+    ) -> Option<Option<u32>> {
+        let mut total: i128 = 0;
+        let mut highest: (u32, i128) = (0, -1);
+        let mut lowest = i128::MAX;
+        for (option_index, option_votes) in counts.iter().enumerate() {
+            if eliminated.get_unchecked(option_index as u32) {
+                continue;
+            }
+            total += option_votes;
+            if option_votes > highest.1 {
+                highest = (option_index as u32, option_votes);
+            }
+            lowest = lowest.min(option_votes);
+        }
+
+        // La mayoría va primero: si solo queda una opción con votos, gana ella
+        if total > 0 && highest.1 * 2 > total {
+            return Some(Some(highest.0));
+        }
+        // Sin votos, o todas las que quedan empatadas: no hay ganador
+        if total == 0 || highest.1 == lowest || last_round {
+            return Some(None);
+        }
+
+        for (option_index, option_votes) in counts.iter().enumerate() {
+            if option_votes == lowest {
+                eliminated.set(option_index as u32, true);
+            }
+        }
+        None
+    }
+
+    /// Comprobar que la propuesta tiene acciones y que se cerró aprobada (ganó la primera
+    /// opción)
+    fn _require_passed(env: &Env, proposal_id: u32, config: &ProposalConfig) -> Result<(), Error> {
+        if config.actions.is_empty() {
+            return Err(fail(Error::NoActions));
+        }
+        if storage::get(env, &DataKey::Outcome(proposal_id)) != Some(Outcome::Passed(0)) {
+            return Err(fail(Error::NotPassed));
+        }
+        Ok(())
+    }
+
+    /// Devolver el depósito al creador si la propuesta tuvo participación suficiente, o
+    /// pasarlo a la tesorería si no alcanzó el quórum o se canceló. Solo ocurre una vez.
+    fn _settle_deposit(env: &Env, proposal_id: u32, outcome: &Outcome) {
+        let key = DataKey::Deposit(proposal_id);
+        let Some(deposit) = storage::get::<Deposit>(env, &key) else {
+            return;
+        };
+        storage::remove(env, &key);
+
+        let refunded = matches!(outcome, Outcome::Passed(_) | Outcome::Rejected);
+        let recipient = if refunded {
+            storage::get(env, &DataKey::Creator(proposal_id)).unwrap_or(deposit.treasury)
+        } else {
+            deposit.treasury
+        };
+
+        token::Client::new(env, &deposit.token).transfer(
+            &env.current_contract_address(),
+            &recipient,
+            &deposit.amount,
+        );
+
+        events::deposit_settled(env, proposal_id, &recipient, deposit.amount, refunded);
+        log!(
+            env,
+            "Depósito de {} {} a {}",
+            deposit.amount,
+            if refunded { "devuelto" } else { "retenido" },
+            recipient
+        );
+    }
+
+    /// Cobrar la tarifa por voto a `payer`, si la hay
+    fn _charge_vote_fee(env: &Env, payer: &Address) {
+        let Some(fees) = env.storage().instance().get::<_, FeeConfig>(&DataKey::Fees) else {
+            return;
+        };
+        if fees.vote_fee > 0 {
+            token::Client::new(env, &fees.token).transfer(payer, &fees.treasury, &fees.vote_fee);
+        }
+    }
+
+    fn _validate_committee(config: &ProposalConfig) -> Result<(), Error> {
+        let committee = &config.committee;
+        if committee.is_empty() {
+            return match config.committee_threshold {
+                0 => Ok(()),
+                _ => Err(fail(Error::InvalidCommittee)),
+            };
+        }
+        if !(1..=committee.len()).contains(&config.committee_threshold) {
+            return Err(fail(Error::InvalidCommittee));
+        }
+        for (index, signer) in committee.iter().enumerate() {
+            if committee
+                .iter()
+                .skip(index + 1)
+                .any(|other| other == signer)
+            {
+                return Err(fail(Error::InvalidCommittee));
+            }
+        }
+        Ok(())
+    }
+
+    fn _vote(env: Env, voter: Address, proposal_id: u32, option_index: u32) -> Result<(), Error> {
+        // El votante debe autorizar
+        voter.require_auth();
+        storage::extend_instance(&env);
+
+        Self::_cast_vote(&env, &voter, proposal_id, option_index, &voter)
+    }
+
+    /// Registrar el voto de `voter`, ya autorizado; `fee_payer` paga la tarifa por voto
+    fn _cast_vote(
+        env: &Env,
+        voter: &Address,
+        proposal_id: u32,
+        option_index: u32,
+        fee_payer: &Address,
+    ) -> Result<(), Error> {
+        log!(
+            env,
+            "Usuario {} votando la opción {} en la propuesta {}",
+            voter,
+            option_index,
+            proposal_id
+        );
+
+        // En una votación secreta se vota con commit/reveal, y en una por ranking con
+        // `vote_ranked`
+        let config = Self::_config(env, proposal_id)?;
+        if config.reveal_end_time.is_some() {
+            return Err(fail(Error::SecretBallotMismatch));
+        }
+        if config.ranked {
+            return Err(fail(Error::RankedBallotMismatch));
+        }
+        if config.credits > 0 {
+            return Err(fail(Error::QuadraticBallotMismatch));
+        }
+
+        // Verificar que la opción exista
+        if option_index >= config.options.len() {
+            return Err(fail(Error::InvalidOption));
+        }
+
+        let weight = Self::_check_can_vote(env, proposal_id, &config, voter)?;
+
+        Self::_record_vote(env, proposal_id, voter, option_index, weight, fee_payer);
+        Ok(())
+    }
+
+    /// Comprobar que `voter` puede votar ahora y devolver el peso con el que cuenta
+    fn _check_can_vote(
+        env: &Env,
+        proposal_id: u32,
+        config: &ProposalConfig,
+        voter: &Address,
+    ) -> Result<i128, Error> {
+        // Verificar que la votación esté activa y dentro de su ventana
+        if !Self::_is_open(env, proposal_id, config) {
+            return Err(fail(Error::VotingNotActive));
+        }
+
+        // Verificar que pueda votar en esta propuesta
+        if !Self::_is_eligible(env, proposal_id, config, voter) {
+            return Err(fail(Error::NotEligible));
+        }
+
+        // Verificar que no haya votado antes ni delegado su voto
+        if Self::_has_voted(env, proposal_id, voter) {
+            return Err(fail(Error::AlreadyVoted));
+        }
+        if storage::has(env, &DataKey::Delegate(proposal_id, voter.clone())) {
+            return Err(fail(Error::AlreadyDelegated));
+        }
+
+        // Su propio peso más el de todos los que delegaron en él
+        Ok(Self::_lock_weight(env, proposal_id, voter)?
+            + Self::_delegated_weight(env, proposal_id, voter))
+    }
+
+    /// Guardar qué votó `voter` y sumar su peso a la opción
+    fn _record_vote(
+        env: &Env,
+        proposal_id: u32,
+        voter: &Address,
+        option_index: u32,
+        weight: i128,
+        fee_payer: &Address,
+    ) {
+        storage::set(
+            env,
+            &DataKey::HasVoted(proposal_id, voter.clone()),
+            &VoteRecord {
+                option: option_index,
+                weight,
+            },
+        );
+        Self::_count_vote(env, proposal_id, voter, option_index, weight, fee_payer);
+    }
+
+    /// Cobrar la tarifa y sumar el peso del voto a la opción
+    fn _count_vote(
+        env: &Env,
+        proposal_id: u32,
+        voter: &Address,
+        option_index: u32,
+        weight: i128,
+        fee_payer: &Address,
+    ) {
+        Self::_charge_vote_fee(env, fee_payer);
+
+        let new_votes = Self::_add_votes(env, proposal_id, voter, option_index, weight);
+
+        events::voted(env, proposal_id, voter, option_index, weight);
+        log!(
+            env,
+            "Voto registrado. Total votos opción {}: {}",
+            option_index,
+            new_votes
+        );
+    }
+
+    /// Sumar (o restar) votos de `voter` a una opción, anotarlo en el registro de votos y
+    /// devolver el nuevo total
+    fn _add_votes(
+        env: &Env,
+        proposal_id: u32,
+        voter: &Address,
+        option_index: u32,
+        delta: i128,
+    ) -> i128 {
+        let key = DataKey::Votes(proposal_id, option_index);
+        let current_votes: i128 = storage::get(env, &key).unwrap_or(0);
+        let new_votes = current_votes + delta;
+        storage::set(env, &key, &new_votes);
+
+        let len_key = DataKey::VoterLogLen(proposal_id);
+        let len: u32 = storage::get(env, &len_key).unwrap_or(0);
+        storage::set(
+            env,
+            &DataKey::VoterLog(proposal_id, len),
+            &VoterEntry {
+                voter: voter.clone(),
+                option: option_index,
+                weight: delta,
+                ledger: env.ledger().sequence(),
+            },
+        );
+        storage::set(env, &len_key, &(len + 1));
+
+        new_votes
+    }
+
+    /// Voto actual de quien quiere cambiarlo o retirarlo
+    fn _existing_vote(
+        env: &Env,
+        proposal_id: u32,
+        config: &ProposalConfig,
+        voter: &Address,
+    ) -> Result<VoteRecord, Error> {
+        if !config.allow_vote_change {
+            return Err(fail(Error::VoteChangeNotAllowed));
+        }
+        if !Self::_is_open(env, proposal_id, config) {
+            return Err(fail(Error::VotingNotActive));
+        }
+
+        storage::get(env, &DataKey::HasVoted(proposal_id, voter.clone()))
+            .ok_or_else(|| fail(Error::NotVoted))
+    }
+
+    /// Peso del voto: 1 sin token de gobernanza; con token, todos los tokens del votante.
+    /// El balance libre pasa al depósito compartido del votante en el contrato, para que
+    /// no pueda moverse y votar otra vez, y el mismo depósito sirve en todas las
+    /// propuestas abiertas en las que participa.
+    fn _lock_weight(env: &Env, proposal_id: u32, voter: &Address) -> Result<i128, Error> {
+        let Some(token) = env.storage().instance().get::<_, Address>(&DataKey::Token) else {
+            return Ok(1);
+        };
+
+        // Ya bloqueó sus tokens en esta propuesta (por ejemplo, al delegar)
+        let locked_key = DataKey::Locked(proposal_id, voter.clone());
+        if let Some(locked) = storage::get(env, &locked_key) {
+            return Ok(locked);
+        }
+
+        let escrow_key = DataKey::Escrow(voter.clone());
+        let mut escrow = storage::get(env, &escrow_key).unwrap_or(Escrow {
+            amount: 0,
+            proposals: 0,
+        });
+
+        let token = token::Client::new(env, &token);
+        let balance = token.balance(voter);
+        if balance > 0 {
+            token.transfer(voter, &env.current_contract_address(), &balance);
+            escrow.amount += balance;
+        }
+        if escrow.amount <= 0 {
+            return Err(fail(Error::NoVotingPower));
+        }
+
+        escrow.proposals += 1;
+        storage::set(env, &escrow_key, &escrow);
+        storage::set(env, &locked_key, &escrow.amount);
+
+        Ok(escrow.amount)
+    }
+
+    /// Peso propio ya bloqueado de una dirección (1 si no hay token de gobernanza)
+    fn _own_weight(env: &Env, proposal_id: u32, voter: &Address) -> i128 {
+        if !env.storage().instance().has(&DataKey::Token) {
+            return 1;
+        }
+        storage::get(env, &DataKey::Locked(proposal_id, voter.clone())).unwrap_or(0)
+    }
+
+    /// Suma del peso de todos los que delegaron, directa o indirectamente, en `voter`
+    fn _delegated_weight(env: &Env, proposal_id: u32, voter: &Address) -> i128 {
+        storage::get(env, &DataKey::DelegatedWeight(proposal_id, voter.clone())).unwrap_or(0)
+    }
+
+    /// Sumar `delta` al peso delegado de todos los que siguen a `from` en su cadena
+    fn _update_delegation_path(env: &Env, proposal_id: u32, from: &Address, delta: i128) {
+        let mut current = from.clone();
+        for _ in 0..MAX_DELEGATION_DEPTH {
+            let Some(next) =
+                storage::get::<Address>(env, &DataKey::Delegate(proposal_id, current.clone()))
+            else {
+                break;
+            };
+            let weight_key = DataKey::DelegatedWeight(proposal_id, next.clone());
+            let weight = Self::_delegated_weight(env, proposal_id, &next) + delta;
+            if weight == 0 {
+                storage::remove(env, &weight_key);
+            } else {
+                storage::set(env, &weight_key, &weight);
+            }
+            current = next;
+        }
+    }
+
+    /// Final de la cadena de delegaciones que empieza en `voter`
+    fn _resolve_delegate(env: &Env, proposal_id: u32, voter: &Address) -> Address {
+        let mut current = voter.clone();
+        for _ in 0..MAX_DELEGATION_DEPTH {
+            match storage::get(env, &DataKey::Delegate(proposal_id, current.clone())) {
+                Some(next) => current = next,
+                None => break,
+            }
+        }
+        current
+    }
+
+    /// Aplicar quórum y umbral a los votos actuales
+    fn _compute_outcome(env: &Env, proposal_id: u32) -> Result<Outcome, Error> {
+        let config = Self::_config(env, proposal_id)?;
+        let votes = Self::get_results(env.clone(), proposal_id)?.votes;
+
+        // Por ranking gana quien tenga mayoría tras eliminar a los menos votados
+        if config.ranked {
+            let total: i128 = votes.iter().sum();
+            if total < config.quorum || total == 0 {
+                return Ok(Outcome::QuorumNotMet);
+            }
+            // El ganador sale del recuento por rondas, que puede necesitar varias páginas
+            return Ok(Outcome::Pending);
+        }
+
+        let mut total: i128 = 0;
+        let mut winner: u32 = 0;
+        let mut winner_votes: i128 = 0;
+        let mut tied = false;
+        for (option_index, option_votes) in votes.iter().enumerate() {
+            total += option_votes;
+            if option_votes > winner_votes {
+                winner = option_index as u32;
+                winner_votes = option_votes;
+                tied = false;
+            } else if option_votes == winner_votes {
+                tied = true;
+            }
+        }
+
+        if total < config.quorum || total == 0 {
+            return Ok(Outcome::QuorumNotMet);
+        }
+
+        let passed = winner_votes * BPS_DENOMINATOR as i128 > total * config.threshold_bps as i128;
+        if passed && !tied {
+            Ok(Outcome::Passed(winner))
+        } else {
+            Ok(Outcome::Rejected)
+        }
+    }
+
+    fn _is_admin(env: &Env, caller: &Address) -> bool {
+        env.storage()
+            .instance()
+            .get::<_, Address>(&DataKey::Admin)
+            .is_some_and(|admin| admin == *caller)
+    }
+
+    fn _require_admin(env: &Env, caller: &Address) -> Result<(), Error> {
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or_else(|| fail(Error::NotInitialized))?;
+
+        if admin != *caller {
+            return Err(fail(Error::NotAdmin));
+        }
+        Ok(())
+    }
+
+    /// Permitir al creador de la propuesta, al administrador o a quien tenga `role`
+    fn _require_authorized(
+        env: &Env,
+        proposal_id: u32,
+        caller: &Address,
+        role: Role,
+    ) -> Result<(), Error> {
+        let stored_creator: Address = storage::get(env, &DataKey::Creator(proposal_id))
+            .ok_or_else(|| fail(Error::ProposalNotFound))?;
+
+        if stored_creator == *caller
+            || Self::_is_admin(env, caller)
+            || storage::has(env, &DataKey::Role(caller.clone(), role))
+        {
+            return Ok(());
+        }
+        Err(fail(Error::Unauthorized))
+    }
+
+    fn _is_eligible(env: &Env, proposal_id: u32, config: &ProposalConfig, voter: &Address) -> bool {
+        !config.restricted || storage::has(env, &DataKey::Eligible(proposal_id, voter.clone()))
+    }
+
+    /// Voto registrado de `voter`. En la propuesta 0 también cuentan los votos de la
+    /// versión original que `migrate` todavía no movió, para que no puedan repetirse.
+    fn _vote_record(env: &Env, proposal_id: u32, voter: &Address) -> Option<VoteRecord> {
+        let record = storage::get(env, &DataKey::HasVoted(proposal_id, voter.clone()));
+        if record.is_none()
+            && proposal_id == 0
+            && env
+                .storage()
+                .instance()
+                .has(&LegacyDataKey::HasVoted(voter.clone()))
+        {
+            return Some(VoteRecord {
+                option: UNKNOWN_OPTION,
+                weight: 1,
+            });
+        }
+        record
+    }
+
+    /// Si `voter` ya votó; en las propuestas cuadráticas, si gastó algún crédito
+    fn _has_voted(env: &Env, proposal_id: u32, voter: &Address) -> bool {
+        Self::_vote_record(env, proposal_id, voter).is_some()
+            || storage::has(env, &DataKey::CreditsSpent(proposal_id, voter.clone()))
+    }
+
+    fn _config(env: &Env, proposal_id: u32) -> Result<ProposalConfig, Error> {
+        storage::get(env, &DataKey::Config(proposal_id))
+            .ok_or_else(|| fail(Error::ProposalNotFound))
+    }
+
+    fn _state(env: &Env, proposal_id: u32) -> BallotState {
+        storage::get(env, &DataKey::State(proposal_id)).unwrap_or(BallotState::Closed)
+    }
+
+    /// Cambiar de estado si el actual es uno de `from`
+    fn _transition(
+        env: &Env,
+        proposal_id: u32,
+        from: &[BallotState],
+        to: BallotState,
+    ) -> Result<(), Error> {
+        let current = Self::_state(env, proposal_id);
+        if !from.contains(&current) {
+            return Err(fail(Error::InvalidTransition));
+        }
+
+        storage::set(env, &DataKey::State(proposal_id), &to);
+
+        events::state_changed(env, proposal_id, current, to);
+        log!(env, "Propuesta {}: {:?} -> {:?}", proposal_id, current, to);
+        Ok(())
+    }
+
+    /// Una votación cuya ventana ya terminó se da por cerrada: solo queda `close_voting`
+    /// para fijar el resultado, no se puede pausar, reanudar ni cancelar
+    fn _require_before_deadline(env: &Env, proposal_id: u32) -> Result<(), Error> {
+        if Self::_deadline_passed(env, &Self::_config(env, proposal_id)?) {
+            return Err(fail(Error::InvalidTransition));
+        }
+        Ok(())
+    }
+
+    /// La votación está abierta si su estado es `Open` y el ledger está dentro de la ventana
+    fn _is_open(env: &Env, proposal_id: u32, config: &ProposalConfig) -> bool {
+        let now = env.ledger().timestamp();
+        let started = now >= config.start_time;
+        let ended = config.end_time.is_some_and(|end_time| now >= end_time);
+
+        Self::_state(env, proposal_id) == BallotState::Open && started && !ended
+    }
+
+    /// Si ya pasó el último plazo de la propuesta (el de revelar, si es secreta)
+    fn _deadline_passed(env: &Env, config: &ProposalConfig) -> bool {
+        let now = env.ledger().timestamp();
+        let deadline = config.reveal_end_time.or(config.end_time);
+
+        deadline.is_some_and(|deadline| now >= deadline)
+    }
+
+    /// Convertir la votación del contrato original en la propuesta 0
+    fn _migrate_legacy_ballot(env: &Env, creator: &Address) {
+        let instance = env.storage().instance();
+        let active: bool = instance.get(&LegacyDataKey::Active).unwrap_or(false);
+        let votes_si: u32 = instance.get(&LegacyDataKey::VotesSi).unwrap_or(0);
+        let votes_no: u32 = instance.get(&LegacyDataKey::VotesNo).unwrap_or(0);
+
+        let config = ProposalConfig {
+            title: String::from_str(env, "Votación original"),
+            description: String::from_str(env, ""),
+            content_hash: BytesN::from_array(env, &[0; 32]),
+            options: Vec::from_array(
+                env,
+                [String::from_str(env, "Si"), String::from_str(env, "No")],
+            ),
+            start_time: 0,
+            end_time: None,
+            quorum: 0,
+            threshold_bps: BPS_DENOMINATOR / 2,
+            restricted: false,
+            allow_vote_change: false,
+            draft: false,
+            reveal_end_time: None,
+            committee: Vec::new(env),
+            committee_threshold: 0,
+            ranked: false,
+            credits: 0,
+            actions: Vec::new(env),
+            timelock: 0,
+        };
+        let state = if active {
+            BallotState::Open
+        } else {
+            BallotState::Closed
+        };
+
+        instance.set(&DataKey::Admin, creator);
+        instance.set(&DataKey::ProposalCount, &1u32);
+        instance.set(&DataKey::Version, &SCHEMA_VERSION);
+        storage::set(env, &DataKey::Creator(0), creator);
+        storage::set(env, &DataKey::Config(0), &config);
+        storage::set(env, &DataKey::State(0), &state);
+        storage::set(env, &DataKey::Votes(0, 0), &(votes_si as i128));
+        storage::set(env, &DataKey::Votes(0, 1), &(votes_no as i128));
+
+        instance.remove(&LegacyDataKey::Creator);
+        instance.remove(&LegacyDataKey::Active);
+        instance.remove(&LegacyDataKey::VotesSi);
+        instance.remove(&LegacyDataKey::VotesNo);
+
+        // Una votación ya cerrada conserva su resultado
+        if !active {
+            if let Ok(outcome) = Self::_compute_outcome(env, 0) {
+                storage::set(env, &DataKey::Outcome(0), &outcome);
+            }
+        }
+    }
+
+    /// La votación terminó si se cerró, se canceló o pasó su último plazo
+    fn _is_finished(env: &Env, proposal_id: u32, config: &ProposalConfig) -> bool {
+        matches!(
+            Self::_state(env, proposal_id),
+            BallotState::Closed | BallotState::Cancelled
+        ) || Self::_deadline_passed(env, config)
+    }
+
+    // --- Funciones de solo lectura ---
+
+    /// Ver el administrador actual del contrato
+    pub fn get_admin(env: Env) -> Result<Address, Error> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or_else(|| fail(Error::NotInitialized))
+    }
+
+    /// Ver la versión del esquema de almacenamiento (0 si tiene datos del contrato original)
+    pub fn version(env: Env) -> u32 {
+        env.storage().instance().get(&DataKey::Version).unwrap_or(0)
+    }
+
+    /// Verificar si una dirección tiene un rol
+    pub fn has_role(env: Env, account: Address, role: Role) -> bool {
+        storage::has(&env, &DataKey::Role(account, role))
+    }
+
+    /// Ver resultados de una propuesta: votos por opción, si está abierta y su ventana
+    pub fn get_results(env: Env, proposal_id: u32) -> Result<Results, Error> {
+        let config = Self::_config(&env, proposal_id)?;
+
+        let mut votes = Vec::new(&env);
+        for option_index in 0..config.options.len() {
+            let option_votes: i128 =
+                storage::get(&env, &DataKey::Votes(proposal_id, option_index)).unwrap_or(0);
+            votes.push_back(option_votes);
+        }
+
+        let state = match Self::_state(&env, proposal_id) {
+            BallotState::Open if Self::_deadline_passed(&env, &config) => BallotState::Closed,
+            state => state,
+        };
+
+        Ok(Results {
+            votes,
+            state,
+            start_time: config.start_time,
+            end_time: config.end_time,
+        })
+    }
+
+    /// Mensaje que el votante firma para `relay_votes`: la dirección de este contrato en
+    /// XDR seguida de la propuesta, la opción y el nonce en big-endian
+    pub fn vote_message(env: Env, proposal_id: u32, option_index: u32, nonce: u64) -> Bytes {
+        let mut message = env.current_contract_address().to_xdr(&env);
+        message.extend_from_array(&proposal_id.to_be_bytes());
+        message.extend_from_array(&option_index.to_be_bytes());
+        message.extend_from_array(&nonce.to_be_bytes());
+        message
+    }
+
+    /// Ver el siguiente nonce que debe usar una dirección en sus votos firmados
+    pub fn get_nonce(env: Env, voter: Address) -> u64 {
+        storage::get(&env, &DataKey::Nonce(voter)).unwrap_or(0)
+    }
+
+    /// Verificar si ya se ejecutaron las acciones de una propuesta
+    pub fn is_executed(env: Env, proposal_id: u32) -> bool {
+        storage::has(&env, &DataKey::Executed(proposal_id))
+    }
+
+    /// Ver en qué punto del timelock están las acciones de una propuesta
+    pub fn get_queue_state(env: Env, proposal_id: u32) -> Result<QueueState, Error> {
+        Self::_config(&env, proposal_id)?;
+        if storage::has(&env, &DataKey::Executed(proposal_id)) {
+            return Ok(QueueState::Executed);
+        }
+        Ok(storage::get(&env, &DataKey::Queue(proposal_id)).unwrap_or(QueueState::NotQueued))
+    }
+
+    /// Ver la espera mínima en cola de todas las propuestas (0 si no se fijó)
+    pub fn get_min_timelock(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&DataKey::MinTimelock)
+            .unwrap_or(0)
+    }
+
+    /// Ver el depósito y la tarifa por voto vigentes
+    pub fn get_fees(env: Env) -> Result<FeeConfig, Error> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Fees)
+            .ok_or_else(|| fail(Error::NoFees))
+    }
+
+    /// Ver los tokens que el contrato guarda para un votante
+    pub fn get_escrow(env: Env, voter: Address) -> Option<Escrow> {
+        storage::get(&env, &DataKey::Escrow(voter))
+    }
+
+    /// Ver el depósito que sigue bloqueado por una propuesta (0 si no hay o ya se resolvió)
+    pub fn get_deposit(env: Env, proposal_id: u32) -> Result<i128, Error> {
+        Self::_config(&env, proposal_id)?;
+        Ok(
+            storage::get::<Deposit>(&env, &DataKey::Deposit(proposal_id))
+                .map_or(0, |deposit| deposit.amount),
+        )
+    }
+
+    /// Ver los datos de una propuesta: creador, estado y configuración con su título,
+    /// descripción y hash del documento
+    pub fn get_proposal(env: Env, proposal_id: u32) -> Result<Proposal, Error> {
+        let creator = storage::get(&env, &DataKey::Creator(proposal_id))
+            .ok_or_else(|| fail(Error::ProposalNotFound))?;
+        let results = Self::get_results(env.clone(), proposal_id)?;
+
+        Ok(Proposal {
+            creator,
+            state: results.state,
+            config: Self::_config(&env, proposal_id)?,
+        })
+    }
+
+    /// Ver el resultado final de una propuesta (`Pending` hasta que se cierre)
+    pub fn get_outcome(env: Env, proposal_id: u32) -> Result<Outcome, Error> {
+        Self::_config(&env, proposal_id)?;
+
+        Ok(storage::get(&env, &DataKey::Outcome(proposal_id)).unwrap_or(Outcome::Pending))
+    }
+
+    /// Ver las opciones de una propuesta
+    pub fn get_options(env: Env, proposal_id: u32) -> Result<Vec<String>, Error> {
+        Ok(Self::_config(&env, proposal_id)?.options)
+    }
+
+    /// Listar los ids de todas las propuestas creadas
+    pub fn list_proposals(env: Env) -> Vec<u32> {
+        let count: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::ProposalCount)
+            .unwrap_or(0);
+
+        let mut ids = Vec::new(&env);
+        for id in 0..count {
+            ids.push_back(id);
+        }
+        ids
+    }
+
+    /// Verificar si una dirección puede votar en una propuesta
+    pub fn is_eligible(env: Env, user: Address, proposal_id: u32) -> Result<bool, Error> {
+        let config = Self::_config(&env, proposal_id)?;
+        Ok(Self::_is_eligible(&env, proposal_id, &config, &user))
+    }
+
+    /// Ver en quién delegó su voto una dirección
+    pub fn get_delegate(env: Env, user: Address, proposal_id: u32) -> Option<Address> {
+        storage::get(&env, &DataKey::Delegate(proposal_id, user))
+    }
+
+    /// Ver el recuento por ranking con segunda vuelta instantánea
+    ///
+    /// En cada ronda cada voto cuenta para su opción preferida que siga en juego. Si
+    /// ninguna tiene más de la mitad, se eliminan las menos votadas (todas las empatadas)
+    /// y se repite. Hay como mucho una ronda por opción. El recuento se hace al cerrar
+    /// (y con `tally_ranked`); hasta que termina, aquí salen solo las rondas ya contadas y
+    /// ningún ganador.
+    pub fn tally_irv(env: Env, proposal_id: u32) -> Result<IrvTally, Error> {
+        let config = Self::_config(&env, proposal_id)?;
+        if !config.ranked {
+            return Err(fail(Error::RankedBallotMismatch));
+        }
+
+        if let Some(tally) = storage::get(&env, &DataKey::IrvTally(proposal_id)) {
+            return Ok(tally);
+        }
+        let rounds = storage::get::<IrvProgress>(&env, &DataKey::IrvProgress(proposal_id))
+            .map_or(Vec::new(&env), |progress| progress.rounds);
+        Ok(IrvTally {
+            winner: None,
+            rounds,
+        })
+    }
+
+    /// Ver cuántos votos dio una dirección a cada opción de una propuesta cuadrática
+    pub fn get_quadratic_votes(
+        env: Env,
+        voter: Address,
+        proposal_id: u32,
+    ) -> Result<Vec<u32>, Error> {
+        let config = Self::_config(&env, proposal_id)?;
+        if config.credits == 0 {
+            return Err(fail(Error::QuadraticBallotMismatch));
+        }
+
+        let mut votes = Vec::new(&env);
+        for option_index in 0..config.options.len() {
+            votes.push_back(
+                storage::get(
+                    &env,
+                    &DataKey::QuadraticVotes(proposal_id, voter.clone(), option_index),
+                )
+                .unwrap_or(0),
+            );
+        }
+        Ok(votes)
+    }
+
+    /// Ver cuántos créditos le quedan a una dirección en una propuesta cuadrática
+    pub fn remaining_credits(env: Env, voter: Address, proposal_id: u32) -> Result<u32, Error> {
+        let config = Self::_config(&env, proposal_id)?;
+        if config.credits == 0 {
+            return Err(fail(Error::QuadraticBallotMismatch));
+        }
+        if !Self::_is_eligible(&env, proposal_id, &config, &voter) {
+            return Ok(0);
+        }
+
+        let spent: i128 =
+            storage::get(&env, &DataKey::CreditsSpent(proposal_id, voter)).unwrap_or(0);
+        Ok(config.credits - spent as u32)
+    }
+
+    /// Ver cuántas entradas tiene el registro de votos de una propuesta
+    pub fn voter_count(env: Env, proposal_id: u32) -> Result<u32, Error> {
+        Self::_config(&env, proposal_id)?;
+        Ok(storage::get(&env, &DataKey::VoterLogLen(proposal_id)).unwrap_or(0))
+    }
+
+    /// Listar el registro de votos de una propuesta desde la posición `start`, con como
+    /// mucho `limit` entradas (y nunca más de `MAX_PAGE_SIZE`)
+    pub fn list_voters(
+        env: Env,
+        proposal_id: u32,
+        start: u32,
+        limit: u32,
+    ) -> Result<Vec<VoterEntry>, Error> {
+        let len = Self::voter_count(env.clone(), proposal_id)?;
+        let end = start.saturating_add(limit.min(MAX_PAGE_SIZE)).min(len);
+
+        let mut entries = Vec::new(&env);
+        for index in start..end {
+            if let Some(entry) = storage::get(&env, &DataKey::VoterLog(proposal_id, index)) {
+                entries.push_back(entry);
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Ver qué firmantes del comité aprobaron ya cerrar una propuesta
+    pub fn get_close_approvals(env: Env, proposal_id: u32) -> Result<Vec<Address>, Error> {
+        Self::_config(&env, proposal_id)?;
+        Ok(storage::get(&env, &DataKey::CloseApprovals(proposal_id)).unwrap_or(Vec::new(&env)))
+    }
 
-        (votes_si, votes_no, active)
+    /// Ver qué votó una dirección en una propuesta
+    pub fn get_vote(env: Env, user: Address, proposal_id: u32) -> Option<VoteRecord> {
+        Self::_vote_record(&env, proposal_id, &user)
     }
 
-    /// Verificar si alguien ya votó
-    pub fn has_voted(env: Env, user: Address) -> bool {
-        env.storage().instance().has(&DataKey::HasVoted(user))
+    /// Verificar si alguien ya votó en una propuesta
+    pub fn has_voted(env: Env, user: Address, proposal_id: u32) -> bool {
+        Self::_has_voted(&env, proposal_id, &user)
     }
 }
 
@@ -0,0 +1,65 @@
+//! Acceso al almacenamiento y política de TTL.
+//!
+//! En `instance` solo vive la configuración global del contrato (administrador, token y
+//! contador de propuestas). Todo lo que crece con cada propuesta o votante va a
+//! `persistent`, así cada llamada solo carga las entradas que usa.
+use soroban_sdk::{Env, IntoVal, TryFromVal, Val};
+
+use crate::DataKey;
+
+const DAY_IN_LEDGERS: u32 = 17_280;
+
+pub(crate) const INSTANCE_BUMP_AMOUNT: u32 = 7 * DAY_IN_LEDGERS;
+pub(crate) const INSTANCE_LIFETIME_THRESHOLD: u32 = INSTANCE_BUMP_AMOUNT - DAY_IN_LEDGERS;
+
+pub(crate) const PERSISTENT_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;
+pub(crate) const PERSISTENT_LIFETIME_THRESHOLD: u32 = PERSISTENT_BUMP_AMOUNT - DAY_IN_LEDGERS;
+
+/// Extender el TTL de la instancia (código y configuración del contrato)
+pub(crate) fn extend_instance(env: &Env) {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+}
+
+/// Extender el TTL de una entrada persistente si existe
+pub(crate) fn extend(env: &Env, key: &DataKey) {
+    if env.storage().persistent().has(key) {
+        env.storage().persistent().extend_ttl(
+            key,
+            PERSISTENT_LIFETIME_THRESHOLD,
+            PERSISTENT_BUMP_AMOUNT,
+        );
+    }
+}
+
+/// Leer una entrada persistente, extendiendo su TTL si existe
+pub(crate) fn get<V: TryFromVal<Env, Val>>(env: &Env, key: &DataKey) -> Option<V> {
+    let value = env.storage().persistent().get(key);
+    if value.is_some() {
+        env.storage().persistent().extend_ttl(
+            key,
+            PERSISTENT_LIFETIME_THRESHOLD,
+            PERSISTENT_BUMP_AMOUNT,
+        );
+    }
+    value
+}
+
+/// Guardar una entrada persistente con el TTL completo
+pub(crate) fn set<V: IntoVal<Env, Val>>(env: &Env, key: &DataKey, value: &V) {
+    env.storage().persistent().set(key, value);
+    env.storage().persistent().extend_ttl(
+        key,
+        PERSISTENT_LIFETIME_THRESHOLD,
+        PERSISTENT_BUMP_AMOUNT,
+    );
+}
+
+pub(crate) fn has(env: &Env, key: &DataKey) -> bool {
+    env.storage().persistent().has(key)
+}
+
+pub(crate) fn remove(env: &Env, key: &DataKey) {
+    env.storage().persistent().remove(key);
+}
@@ -2,12 +2,87 @@
 
 use super::*;
 use soroban_sdk::{
-    testutils::{Address as _, MockAuth, MockAuthInvoke},
-    Address, Env, IntoVal,
+    symbol_short,
+    testutils::{
+        storage::{Instance as _, Persistent as _},
+        Address as _, Events, Ledger, MockAuth, MockAuthInvoke,
+    },
+    token::{StellarAssetClient, TokenClient},
+    vec, Address, Env, IntoVal, InvokeError, String, Symbol,
 };
 
 extern crate std;
 
+use std::fmt::Debug;
+
+/// Comprobar que una llamada `try_` falló con `error`. Con la feature `legacy` el contrato
+/// aborta con un panic y el cliente solo ve `InvokeError::Abort`, así que el error se
+/// identifica por el mensaje del panic que el host deja en los eventos de diagnóstico
+/// (el `{:?}` con el que aborta `fail`). Debe llamarse justo después de la invocación.
+fn assert_error<T: Debug>(env: &Env, result: Result<T, Result<Error, InvokeError>>, error: Error) {
+    #[cfg(not(feature = "legacy"))]
+    {
+        let _ = env;
+        match result {
+            Err(Ok(got)) => assert_eq!(got, error),
+            other => panic!("se esperaba {:?}, se obtuvo {:?}", error, other),
+        }
+    }
+    #[cfg(feature = "legacy")]
+    {
+        match result {
+            Err(Err(InvokeError::Abort)) => {}
+            other => panic!(
+                "se esperaba un panic con {:?}, se obtuvo {:?}",
+                error, other
+            ),
+        }
+        // Los tres últimos eventos de la invocación fallida son el panic capturado en el
+        // contrato, el error que produce y el fallo de la llamada
+        let events = env.host().get_diagnostic_events().unwrap();
+        let panic_event = events
+            .0
+            .iter()
+            .rev()
+            .nth(2)
+            .map(|e| std::format!("{:?}", e));
+        let expected = std::format!("caught panic '{:?}'", error);
+        assert!(
+            panic_event.as_ref().is_some_and(|e| e.contains(&expected)),
+            "se esperaba un panic con {:?}, el host registró {:?}",
+            error,
+            panic_event
+        );
+    }
+}
+
+fn si_no_config(env: &Env) -> ProposalConfig {
+    ProposalConfig {
+        title: String::from_str(env, "¿Aprobamos el presupuesto?"),
+        description: String::from_str(env, "ipfs://presupuesto"),
+        content_hash: BytesN::from_array(env, &[7; 32]),
+        options: vec![
+            env,
+            String::from_str(env, "Si"),
+            String::from_str(env, "No"),
+        ],
+        start_time: 0,
+        end_time: None,
+        quorum: 0,
+        threshold_bps: 5_000,
+        restricted: false,
+        allow_vote_change: false,
+        draft: false,
+        reveal_end_time: None,
+        committee: vec![env],
+        committee_threshold: 0,
+        ranked: false,
+        credits: 0,
+        actions: vec![env],
+        timelock: 0,
+    }
+}
+
 #[test]
 fn test_init_voting() {
     let env = Env::default();
@@ -16,7 +91,6 @@ fn test_init_voting() {
     let creator = Address::generate(&env);
     std::println!("👤 Creador: {:?}", creator);
 
-    // Inicializar votación
     // Inicializar votación
     client
         .mock_auths(&[MockAuth {
@@ -24,25 +98,39 @@ fn test_init_voting() {
             invoke: &MockAuthInvoke {
                 contract: &contract_id,
                 fn_name: "init",
-                args: (creator.clone(),).into_val(&env),
+                args: (creator.clone(), None::<Address>).into_val(&env),
+                sub_invokes: &[],
+            },
+        }])
+        .init(&creator, &None);
+
+    let proposal_id = client
+        .mock_auths(&[MockAuth {
+            address: &creator,
+            invoke: &MockAuthInvoke {
+                contract: &contract_id,
+                fn_name: "create_proposal",
+                args: (creator.clone(), si_no_config(&env)).into_val(&env),
                 sub_invokes: &[],
             },
         }])
-        .init(&creator);
+        .create_proposal(&creator, &si_no_config(&env));
 
     std::println!("✅ Votación inicializada");
 
     // Verificar estado inicial
-    let (votes_si, votes_no, active) = client.get_results();
+    let results = client.get_results(&proposal_id);
+    let votes_si = results.votes.get(0).unwrap();
+    let votes_no = results.votes.get(1).unwrap();
 
     std::println!("📊 Estado inicial:");
     std::println!("   - Votos SI: {}", votes_si);
     std::println!("   - Votos NO: {}", votes_no);
-    std::println!("   - Activa: {}", active);
+    std::println!("   - Estado: {:?}", results.state);
 
     assert_eq!(votes_si, 0);
     assert_eq!(votes_no, 0);
-    assert_eq!(active, true);
+    assert_eq!(results.state, BallotState::Open);
 }
 #[test]
 fn test_vote_si() {
@@ -62,11 +150,23 @@ fn test_vote_si() {
             invoke: &MockAuthInvoke {
                 contract: &contract_id,
                 fn_name: "init",
-                args: (creator.clone(),).into_val(&env),
+                args: (creator.clone(), None::<Address>).into_val(&env),
                 sub_invokes: &[],
             },
         }])
-        .init(&creator);
+        .init(&creator, &None);
+
+    let proposal_id = client
+        .mock_auths(&[MockAuth {
+            address: &creator,
+            invoke: &MockAuthInvoke {
+                contract: &contract_id,
+                fn_name: "create_proposal",
+                args: (creator.clone(), si_no_config(&env)).into_val(&env),
+                sub_invokes: &[],
+            },
+        }])
+        .create_proposal(&creator, &si_no_config(&env));
 
     std::println!("✅ Votación inicializada");
 
@@ -77,17 +177,19 @@ fn test_vote_si() {
             invoke: &MockAuthInvoke {
                 contract: &contract_id,
                 fn_name: "vote_si",
-                args: (voter.clone(),).into_val(&env),
+                args: (voter.clone(), proposal_id).into_val(&env),
                 sub_invokes: &[],
             },
         }])
-        .vote_si(&voter);
+        .vote_si(&voter, &proposal_id);
 
     std::println!("👍 Voto SI registrado");
 
     // Verificar resultados
-    let (votes_si, votes_no, active) = client.get_results();
-    let has_voted = client.has_voted(&voter);
+    let results = client.get_results(&proposal_id);
+    let votes_si = results.votes.get(0).unwrap();
+    let votes_no = results.votes.get(1).unwrap();
+    let has_voted = client.has_voted(&voter, &proposal_id);
 
     std::println!("📊 Resultados después del voto:");
     std::println!("   - Votos SI: {}", votes_si);
@@ -96,7 +198,7 @@ fn test_vote_si() {
 
     assert_eq!(votes_si, 1);
     assert_eq!(votes_no, 0);
-    assert_eq!(has_voted, true);
+    assert!(has_voted);
 }
 #[test]
 fn test_vote_no() {
@@ -116,11 +218,23 @@ fn test_vote_no() {
             invoke: &MockAuthInvoke {
                 contract: &contract_id,
                 fn_name: "init",
-                args: (creator.clone(),).into_val(&env),
+                args: (creator.clone(), None::<Address>).into_val(&env),
                 sub_invokes: &[],
             },
         }])
-        .init(&creator);
+        .init(&creator, &None);
+
+    let proposal_id = client
+        .mock_auths(&[MockAuth {
+            address: &creator,
+            invoke: &MockAuthInvoke {
+                contract: &contract_id,
+                fn_name: "create_proposal",
+                args: (creator.clone(), si_no_config(&env)).into_val(&env),
+                sub_invokes: &[],
+            },
+        }])
+        .create_proposal(&creator, &si_no_config(&env));
 
     // Votar NO
     client
@@ -129,16 +243,18 @@ fn test_vote_no() {
             invoke: &MockAuthInvoke {
                 contract: &contract_id,
                 fn_name: "vote_no",
-                args: (voter.clone(),).into_val(&env),
+                args: (voter.clone(), proposal_id).into_val(&env),
                 sub_invokes: &[],
             },
         }])
-        .vote_no(&voter);
+        .vote_no(&voter, &proposal_id);
 
     std::println!("👎 Voto NO registrado");
 
     // Verificar resultados
-    let (votes_si, votes_no, _) = client.get_results();
+    let results = client.get_results(&proposal_id);
+    let votes_si = results.votes.get(0).unwrap();
+    let votes_no = results.votes.get(1).unwrap();
 
     std::println!("📊 Resultados:");
     std::println!("   - Votos SI: {}", votes_si);
@@ -166,11 +282,23 @@ fn test_cannot_vote_twice() {
             invoke: &MockAuthInvoke {
                 contract: &contract_id,
                 fn_name: "init",
-                args: (creator.clone(),).into_val(&env),
+                args: (creator.clone(), None::<Address>).into_val(&env),
+                sub_invokes: &[],
+            },
+        }])
+        .init(&creator, &None);
+
+    let proposal_id = client
+        .mock_auths(&[MockAuth {
+            address: &creator,
+            invoke: &MockAuthInvoke {
+                contract: &contract_id,
+                fn_name: "create_proposal",
+                args: (creator.clone(), si_no_config(&env)).into_val(&env),
                 sub_invokes: &[],
             },
         }])
-        .init(&creator);
+        .create_proposal(&creator, &si_no_config(&env));
 
     // Primer voto (SI)
     client
@@ -179,11 +307,11 @@ fn test_cannot_vote_twice() {
             invoke: &MockAuthInvoke {
                 contract: &contract_id,
                 fn_name: "vote_si",
-                args: (voter.clone(),).into_val(&env),
+                args: (voter.clone(), proposal_id).into_val(&env),
                 sub_invokes: &[],
             },
         }])
-        .vote_si(&voter);
+        .vote_si(&voter, &proposal_id);
 
     std::println!("✅ Primer voto (SI) registrado");
 
@@ -194,18 +322,20 @@ fn test_cannot_vote_twice() {
             invoke: &MockAuthInvoke {
                 contract: &contract_id,
                 fn_name: "vote_no",
-                args: (voter.clone(),).into_val(&env),
+                args: (voter.clone(), proposal_id).into_val(&env),
                 sub_invokes: &[],
             },
         }])
-        .try_vote_no(&voter);
+        .try_vote_no(&voter, &proposal_id);
 
     std::println!("🚫 Segundo voto bloqueado correctamente");
 
     assert!(result.is_err());
 
     // Verificar que solo hay un voto
-    let (votes_si, votes_no, _) = client.get_results();
+    let results = client.get_results(&proposal_id);
+    let votes_si = results.votes.get(0).unwrap();
+    let votes_no = results.votes.get(1).unwrap();
     assert_eq!(votes_si, 1);
     assert_eq!(votes_no, 0);
 }
@@ -227,11 +357,23 @@ fn test_close_voting() {
             invoke: &MockAuthInvoke {
                 contract: &contract_id,
                 fn_name: "init",
-                args: (creator.clone(),).into_val(&env),
+                args: (creator.clone(), None::<Address>).into_val(&env),
+                sub_invokes: &[],
+            },
+        }])
+        .init(&creator, &None);
+
+    let proposal_id = client
+        .mock_auths(&[MockAuth {
+            address: &creator,
+            invoke: &MockAuthInvoke {
+                contract: &contract_id,
+                fn_name: "create_proposal",
+                args: (creator.clone(), si_no_config(&env)).into_val(&env),
                 sub_invokes: &[],
             },
         }])
-        .init(&creator);
+        .create_proposal(&creator, &si_no_config(&env));
 
     // Votar antes de cerrar
     client
@@ -240,11 +382,11 @@ fn test_close_voting() {
             invoke: &MockAuthInvoke {
                 contract: &contract_id,
                 fn_name: "vote_si",
-                args: (voter.clone(),).into_val(&env),
+                args: (voter.clone(), proposal_id).into_val(&env),
                 sub_invokes: &[],
             },
         }])
-        .vote_si(&voter);
+        .vote_si(&voter, &proposal_id);
 
     std::println!("✅ Voto registrado antes de cerrar");
 
@@ -255,25 +397,27 @@ fn test_close_voting() {
             invoke: &MockAuthInvoke {
                 contract: &contract_id,
                 fn_name: "close_voting",
-                args: (creator.clone(),).into_val(&env),
+                args: (creator.clone(), proposal_id).into_val(&env),
                 sub_invokes: &[],
             },
         }])
-        .close_voting(&creator);
+        .close_voting(&creator, &proposal_id);
 
     std::println!("🔒 Votación cerrada por el creador");
 
     // Verificar que está cerrada
-    let (votes_si, votes_no, active) = client.get_results();
+    let results = client.get_results(&proposal_id);
+    let votes_si = results.votes.get(0).unwrap();
+    let votes_no = results.votes.get(1).unwrap();
 
     std::println!("📊 Estado final:");
     std::println!("   - Votos SI: {}", votes_si);
     std::println!("   - Votos NO: {}", votes_no);
-    std::println!("   - Activa: {}", active);
+    std::println!("   - Estado: {:?}", results.state);
 
     assert_eq!(votes_si, 1);
     assert_eq!(votes_no, 0);
-    assert_eq!(active, false);
+    assert_eq!(results.state, BallotState::Closed);
 
     // Intentar votar en votación cerrada (debe fallar)
     let new_voter = Address::generate(&env);
@@ -283,13 +427,2302 @@ fn test_close_voting() {
             invoke: &MockAuthInvoke {
                 contract: &contract_id,
                 fn_name: "vote_no",
-                args: (new_voter.clone(),).into_val(&env),
+                args: (new_voter.clone(), proposal_id).into_val(&env),
                 sub_invokes: &[],
             },
         }])
-        .try_vote_no(&new_voter);
+        .try_vote_no(&new_voter, &proposal_id);
 
     std::println!("🚫 Voto en votación cerrada bloqueado");
 
     assert!(result.is_err());
 }
+
+#[test]
+fn test_multiple_proposals() {
+    std::println!("🧪 Test: Varias propuestas en el mismo contrato");
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(SimpleVoting, ());
+    let client = SimpleVotingClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let voter = Address::generate(&env);
+
+    client.init(&admin, &None);
+
+    let first = client.create_proposal(&creator, &si_no_config(&env));
+    let second = client.create_proposal(&creator, &si_no_config(&env));
+
+    assert_eq!(first, 0);
+    assert_eq!(second, 1);
+    assert_eq!(client.list_proposals(), vec![&env, 0, 1]);
+
+    // El mismo votante puede votar en cada propuesta una vez
+    client.vote_si(&voter, &first);
+    client.vote_no(&voter, &second);
+
+    assert_eq!(client.get_results(&first).votes, vec![&env, 1, 0]);
+    assert_eq!(client.get_results(&second).votes, vec![&env, 0, 1]);
+
+    // Cerrar una propuesta no afecta a la otra
+    client.close_voting(&creator, &first);
+
+    assert_eq!(client.get_results(&first).state, BallotState::Closed);
+    assert_eq!(client.get_results(&second).state, BallotState::Open);
+}
+
+#[test]
+fn test_proposal_errors() {
+    std::println!("🧪 Test: Errores de propuestas");
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(SimpleVoting, ());
+    let client = SimpleVotingClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let voter = Address::generate(&env);
+
+    // No se pueden crear propuestas antes de inicializar
+    assert_error(
+        &env,
+        client.try_create_proposal(&creator, &si_no_config(&env)),
+        Error::NotInitialized,
+    );
+
+    client.init(&admin, &None);
+    assert_error(
+        &env,
+        client.try_init(&admin, &None),
+        Error::AlreadyInitialized,
+    );
+
+    // Propuesta inexistente
+    assert_error(
+        &env,
+        client.try_vote_si(&voter, &7),
+        Error::ProposalNotFound,
+    );
+    assert_error(&env, client.try_get_results(&7), Error::ProposalNotFound);
+
+    // Un votante cualquiera no puede cerrarla
+    let proposal_id = client.create_proposal(&creator, &si_no_config(&env));
+    assert_error(
+        &env,
+        client.try_close_voting(&voter, &proposal_id),
+        Error::Unauthorized,
+    );
+}
+
+#[test]
+fn test_multiple_choice() {
+    std::println!("🧪 Test: Propuesta con varias opciones");
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(SimpleVoting, ());
+    let client = SimpleVotingClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let creator = Address::generate(&env);
+
+    client.init(&admin, &None);
+
+    let options = vec![
+        &env,
+        String::from_str(&env, "Rojo"),
+        String::from_str(&env, "Verde"),
+        String::from_str(&env, "Azul"),
+    ];
+    let config = ProposalConfig {
+        options: options.clone(),
+        ..si_no_config(&env)
+    };
+    let proposal_id = client.create_proposal(&creator, &config);
+    assert_eq!(client.get_options(&proposal_id), options);
+
+    client.vote(&Address::generate(&env), &proposal_id, &2);
+    client.vote(&Address::generate(&env), &proposal_id, &2);
+    client.vote(&Address::generate(&env), &proposal_id, &0);
+
+    let results = client.get_results(&proposal_id);
+    std::println!("📊 Resultados: {:?}", results.votes);
+
+    assert_eq!(results.votes, vec![&env, 1, 0, 2]);
+    assert_eq!(results.state, BallotState::Open);
+
+    // Opción fuera de rango
+    assert_error(
+        &env,
+        client.try_vote(&Address::generate(&env), &proposal_id, &3),
+        Error::InvalidOption,
+    );
+
+    // Se necesitan al menos dos opciones
+    let single = ProposalConfig {
+        options: vec![&env, String::from_str(&env, "Unica")],
+        ..si_no_config(&env)
+    };
+    assert_error(
+        &env,
+        client.try_create_proposal(&creator, &single),
+        Error::InvalidOptions,
+    );
+}
+
+#[test]
+fn test_voting_window() {
+    std::println!("🧪 Test: Ventana de votación por timestamp");
+
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+    let contract_id = env.register(SimpleVoting, ());
+    let client = SimpleVotingClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let voter = Address::generate(&env);
+
+    client.init(&admin, &None);
+
+    let config = ProposalConfig {
+        start_time: 2_000,
+        end_time: Some(3_000),
+        ..si_no_config(&env)
+    };
+    let proposal_id = client.create_proposal(&creator, &config);
+
+    let results = client.get_results(&proposal_id);
+    assert_eq!(results.start_time, 2_000);
+    assert_eq!(results.end_time, Some(3_000));
+    assert_eq!(results.state, BallotState::Open);
+
+    // Antes de empezar
+    assert_error(
+        &env,
+        client.try_vote_si(&voter, &proposal_id),
+        Error::VotingNotActive,
+    );
+
+    // Dentro de la ventana
+    env.ledger().set_timestamp(2_000);
+    assert_eq!(client.get_results(&proposal_id).state, BallotState::Open);
+    client.vote_si(&voter, &proposal_id);
+
+    // Al terminar la ventana se cierra sola, sin llamar a close_voting
+    env.ledger().set_timestamp(3_000);
+    let late_voter = Address::generate(&env);
+    assert_error(
+        &env,
+        client.try_vote_no(&late_voter, &proposal_id),
+        Error::VotingNotActive,
+    );
+
+    let results = client.get_results(&proposal_id);
+    assert_eq!(results.state, BallotState::Closed);
+    assert_eq!(results.votes, vec![&env, 1, 0]);
+
+    // La ventana debe terminar después de empezar
+    let invalid = ProposalConfig {
+        start_time: 3_000,
+        end_time: Some(3_000),
+        ..si_no_config(&env)
+    };
+    assert_error(
+        &env,
+        client.try_create_proposal(&creator, &invalid),
+        Error::InvalidWindow,
+    );
+}
+
+#[test]
+fn test_token_weighted_voting() {
+    std::println!("🧪 Test: Votos ponderados por token de gobernanza");
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(SimpleVoting, ());
+    let client = SimpleVotingClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let whale = Address::generate(&env);
+    let small = Address::generate(&env);
+    let accomplice = Address::generate(&env);
+
+    let token = env.register_stellar_asset_contract_v2(admin.clone());
+    let token_admin = StellarAssetClient::new(&env, &token.address());
+    let token_client = TokenClient::new(&env, &token.address());
+    token_admin.mint(&whale, &1_000);
+    token_admin.mint(&small, &10);
+
+    client.init(&admin, &Some(token.address()));
+    let proposal_id = client.create_proposal(&creator, &si_no_config(&env));
+
+    client.vote_si(&small, &proposal_id);
+    client.vote_no(&whale, &proposal_id);
+
+    assert_eq!(
+        client.get_results(&proposal_id).votes,
+        vec![&env, 10, 1_000]
+    );
+
+    // Los tokens quedan bloqueados: no se pueden pasar a otra cuenta para votar otra vez
+    assert_eq!(token_client.balance(&whale), 0);
+    assert_eq!(token_client.balance(&contract_id), 1_010);
+    assert_error(
+        &env,
+        client.try_vote_no(&accomplice, &proposal_id),
+        Error::NoVotingPower,
+    );
+
+    // Y no se pueden recuperar hasta que termine la votación
+    assert_error(
+        &env,
+        client.try_unlock_tokens(&whale, &proposal_id),
+        Error::VotingStillOpen,
+    );
+
+    // Los mismos tokens cuentan a la vez en otra propuesta abierta, junto con los nuevos
+    let second = client.create_proposal(&creator, &si_no_config(&env));
+    token_admin.mint(&whale, &500);
+    client.vote_si(&whale, &second);
+    assert_eq!(client.get_results(&second).votes, vec![&env, 1_500, 0]);
+    assert_eq!(
+        client.get_escrow(&whale),
+        Some(Escrow {
+            amount: 1_500,
+            proposals: 2,
+        })
+    );
+    assert_eq!(token_client.balance(&contract_id), 1_510);
+
+    client.close_voting(&creator, &proposal_id);
+
+    // Mientras la segunda siga abierta no se devuelve nada
+    assert_eq!(client.unlock_tokens(&whale, &proposal_id), 0);
+    assert_eq!(token_client.balance(&whale), 0);
+    assert_error(
+        &env,
+        client.try_unlock_tokens(&whale, &proposal_id),
+        Error::NothingLocked,
+    );
+    assert_error(
+        &env,
+        client.try_unlock_tokens(&whale, &second),
+        Error::VotingStillOpen,
+    );
+
+    client.close_voting(&creator, &second);
+    assert_eq!(client.unlock_tokens(&whale, &second), 1_500);
+    assert_eq!(token_client.balance(&whale), 1_500);
+
+    // Ya liberados, reabrir permitiría pasarlos a otra cuenta y votar otra vez
+    token_client.transfer(&whale, &accomplice, &1_500);
+    assert_error(
+        &env,
+        client.try_reopen(&creator, &second),
+        Error::InvalidTransition,
+    );
+    assert_error(
+        &env,
+        client.try_reopen(&creator, &proposal_id),
+        Error::InvalidTransition,
+    );
+    assert_eq!(client.get_results(&second).votes, vec![&env, 1_500, 0]);
+    assert_eq!(client.get_escrow(&whale), None);
+    assert_eq!(client.unlock_tokens(&small, &proposal_id), 10);
+}
+
+#[test]
+fn test_outcome_rules() {
+    std::println!("🧪 Test: Quórum, umbral y resultado final");
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(SimpleVoting, ());
+    let client = SimpleVotingClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let creator = Address::generate(&env);
+
+    client.init(&admin, &None);
+
+    // Dos tercios con quórum de 3 votos
+    let config = ProposalConfig {
+        quorum: 3,
+        threshold_bps: 6_666,
+        ..si_no_config(&env)
+    };
+
+    // 2 SI de 3: justo dos tercios, se aprueba
+    let passed = client.create_proposal(&creator, &config);
+    client.vote_si(&Address::generate(&env), &passed);
+    client.vote_si(&Address::generate(&env), &passed);
+    client.vote_no(&Address::generate(&env), &passed);
+    assert_eq!(client.get_outcome(&passed), Outcome::Pending);
+    client.close_voting(&creator, &passed);
+    assert_eq!(client.get_outcome(&passed), Outcome::Passed(0));
+
+    // 3 SI de 5: mayoría simple pero no dos tercios
+    let rejected = client.create_proposal(&creator, &config);
+    for _ in 0..3 {
+        client.vote_si(&Address::generate(&env), &rejected);
+    }
+    for _ in 0..2 {
+        client.vote_no(&Address::generate(&env), &rejected);
+    }
+    client.close_voting(&creator, &rejected);
+    assert_eq!(client.get_outcome(&rejected), Outcome::Rejected);
+
+    // Solo 2 votos con quórum de 3
+    let no_quorum = client.create_proposal(&creator, &config);
+    client.vote_si(&Address::generate(&env), &no_quorum);
+    client.vote_si(&Address::generate(&env), &no_quorum);
+    client.close_voting(&creator, &no_quorum);
+    assert_eq!(client.get_outcome(&no_quorum), Outcome::QuorumNotMet);
+
+    // Un empate con mayoría simple no aprueba nada
+    let tie = client.create_proposal(&creator, &si_no_config(&env));
+    client.vote_si(&Address::generate(&env), &tie);
+    client.vote_no(&Address::generate(&env), &tie);
+    client.close_voting(&creator, &tie);
+    assert_eq!(client.get_outcome(&tie), Outcome::Rejected);
+
+    // Umbral fuera de rango
+    let invalid = ProposalConfig {
+        threshold_bps: 10_001,
+        ..si_no_config(&env)
+    };
+    assert_error(
+        &env,
+        client.try_create_proposal(&creator, &invalid),
+        Error::InvalidThreshold,
+    );
+}
+
+#[test]
+fn test_eligible_voters() {
+    std::println!("🧪 Test: Lista de votantes habilitados");
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(SimpleVoting, ());
+    let client = SimpleVotingClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let outsider = Address::generate(&env);
+
+    client.init(&admin, &None);
+
+    let config = ProposalConfig {
+        restricted: true,
+        ..si_no_config(&env)
+    };
+    let proposal_id = client.create_proposal(&creator, &config);
+
+    // Importar la lista de golpe
+    client.add_voters(
+        &creator,
+        &proposal_id,
+        &vec![&env, alice.clone(), bob.clone()],
+    );
+
+    assert!(client.is_eligible(&alice, &proposal_id));
+    assert!(!client.is_eligible(&outsider, &proposal_id));
+
+    client.vote_si(&alice, &proposal_id);
+    assert_error(
+        &env,
+        client.try_vote_si(&outsider, &proposal_id),
+        Error::NotEligible,
+    );
+
+    // Quitar y volver a añadir
+    client.remove_voter(&creator, &proposal_id, &bob);
+    assert_error(
+        &env,
+        client.try_vote_no(&bob, &proposal_id),
+        Error::NotEligible,
+    );
+    client.add_voter(&creator, &proposal_id, &outsider);
+    client.vote_no(&outsider, &proposal_id);
+
+    assert_eq!(client.get_results(&proposal_id).votes, vec![&env, 1, 1]);
+
+    // Solo el creador gestiona la lista
+    assert_error(
+        &env,
+        client.try_add_voter(&alice, &proposal_id, &bob),
+        Error::Unauthorized,
+    );
+
+    // En una propuesta abierta todos pueden votar
+    let open = client.create_proposal(&creator, &si_no_config(&env));
+    assert!(client.is_eligible(&outsider, &open));
+}
+
+#[test]
+fn test_delegation_chain() {
+    std::println!("🧪 Test: Delegación de votos en cadena");
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(SimpleVoting, ());
+    let client = SimpleVotingClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let carol = Address::generate(&env);
+    let dave = Address::generate(&env);
+
+    client.init(&admin, &None);
+    let proposal_id = client.create_proposal(&creator, &si_no_config(&env));
+
+    // alice -> bob -> carol
+    client.delegate(&alice, &proposal_id, &bob);
+    client.delegate(&bob, &proposal_id, &carol);
+    assert_eq!(client.get_delegate(&alice, &proposal_id), Some(bob.clone()));
+    assert_eq!(client.get_delegate(&carol, &proposal_id), None);
+
+    // carol -> alice cerraría el ciclo
+    assert_error(
+        &env,
+        client.try_delegate(&carol, &proposal_id, &alice),
+        Error::DelegationCycle,
+    );
+
+    // dave delega y luego revoca antes de que carol vote
+    client.delegate(&dave, &proposal_id, &carol);
+    client.revoke_delegation(&dave, &proposal_id);
+    assert_eq!(client.get_delegate(&dave, &proposal_id), None);
+
+    // No se puede revocar una delegación que no existe
+    assert_error(
+        &env,
+        client.try_revoke_delegation(&carol, &proposal_id),
+        Error::NotDelegated,
+    );
+
+    // Quien delegó no puede votar directamente
+    assert_error(
+        &env,
+        client.try_vote_no(&alice, &proposal_id),
+        Error::AlreadyDelegated,
+    );
+
+    // El voto de carol cuenta por ella, bob y alice
+    client.vote_si(&carol, &proposal_id);
+    client.vote_no(&dave, &proposal_id);
+    assert_eq!(client.get_results(&proposal_id).votes, vec![&env, 3, 1]);
+
+    // Una vez que carol votó ya no se puede revocar ni delegar en la cadena
+    assert_error(
+        &env,
+        client.try_revoke_delegation(&alice, &proposal_id),
+        Error::DelegateAlreadyVoted,
+    );
+    let erin = Address::generate(&env);
+    assert_error(
+        &env,
+        client.try_delegate(&erin, &proposal_id, &alice),
+        Error::DelegateAlreadyVoted,
+    );
+
+    // El límite cuenta toda la cadena, crezca por el principio o por el final
+    let long = client.create_proposal(&creator, &si_no_config(&env));
+    let end = Address::generate(&env);
+    let mut head = end.clone();
+    for _ in 0..MAX_DELEGATION_DEPTH {
+        let next = Address::generate(&env);
+        client.delegate(&next, &long, &head);
+        head = next;
+    }
+    assert_error(
+        &env,
+        client.try_delegate(&Address::generate(&env), &long, &head),
+        Error::DelegationTooDeep,
+    );
+    assert_error(
+        &env,
+        client.try_delegate(&end, &long, &Address::generate(&env)),
+        Error::DelegationTooDeep,
+    );
+
+    // Al revocar el primer eslabón, el final deja de recibir su peso
+    client.revoke_delegation(&head, &long);
+    client.vote_si(&end, &long);
+    assert_eq!(
+        client.get_results(&long).votes,
+        vec![&env, MAX_DELEGATION_DEPTH as i128, 0]
+    );
+}
+
+#[test]
+fn test_change_vote() {
+    std::println!("🧪 Test: Cambiar y retirar el voto");
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(SimpleVoting, ());
+    let client = SimpleVotingClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let voter = Address::generate(&env);
+    let follower = Address::generate(&env);
+
+    client.init(&admin, &None);
+
+    let config = ProposalConfig {
+        allow_vote_change: true,
+        ..si_no_config(&env)
+    };
+    let proposal_id = client.create_proposal(&creator, &config);
+
+    // El voto cambiado arrastra también lo delegado
+    client.delegate(&follower, &proposal_id, &voter);
+    client.vote_si(&voter, &proposal_id);
+    assert_eq!(
+        client.get_vote(&voter, &proposal_id),
+        Some(VoteRecord {
+            option: 0,
+            weight: 2
+        })
+    );
+
+    client.change_vote(&voter, &proposal_id, &1);
+    assert_eq!(client.get_results(&proposal_id).votes, vec![&env, 0, 2]);
+    assert_eq!(client.get_vote(&voter, &proposal_id).unwrap().option, 1);
+
+    client.retract_vote(&voter, &proposal_id);
+    assert_eq!(client.get_results(&proposal_id).votes, vec![&env, 0, 0]);
+    assert!(!client.has_voted(&voter, &proposal_id));
+    assert_error(
+        &env,
+        client.try_retract_vote(&voter, &proposal_id),
+        Error::NotVoted,
+    );
+
+    // Después de retirarlo puede volver a votar
+    client.vote_si(&voter, &proposal_id);
+    assert_eq!(client.get_results(&proposal_id).votes, vec![&env, 2, 0]);
+
+    // Cerrada la votación, el voto queda fijo
+    client.close_voting(&creator, &proposal_id);
+    assert_error(
+        &env,
+        client.try_change_vote(&voter, &proposal_id, &1),
+        Error::VotingNotActive,
+    );
+
+    // Sin la opción activada no se puede cambiar
+    let fixed = client.create_proposal(&creator, &si_no_config(&env));
+    client.vote_si(&voter, &fixed);
+    assert_error(
+        &env,
+        client.try_change_vote(&voter, &fixed, &1),
+        Error::VoteChangeNotAllowed,
+    );
+}
+
+#[test]
+fn test_events() {
+    std::println!("🧪 Test: Eventos de la votación");
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(SimpleVoting, ());
+    let client = SimpleVotingClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let voter = Address::generate(&env);
+
+    client.init(&admin, &None);
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol_short!("init"), admin.clone()).into_val(&env),
+                None::<Address>.into_val(&env),
+            ),
+        ]
+    );
+
+    let proposal_id = client.create_proposal(&creator, &si_no_config(&env));
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol_short!("created"), proposal_id).into_val(&env),
+                creator.into_val(&env),
+            ),
+        ]
+    );
+
+    client.vote_no(&voter, &proposal_id);
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol_short!("vote"), proposal_id, voter.clone()).into_val(&env),
+                (1u32, 1i128).into_val(&env),
+            ),
+        ]
+    );
+
+    client.close_voting(&creator, &proposal_id);
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol_short!("state"), proposal_id).into_val(&env),
+                (BallotState::Open, BallotState::Closed).into_val(&env),
+            ),
+            (
+                contract_id.clone(),
+                (symbol_short!("closed"), proposal_id).into_val(&env),
+                Outcome::Passed(1).into_val(&env),
+            ),
+        ]
+    );
+}
+
+fn commitment(env: &Env, option_index: u32, salt: &BytesN<32>) -> BytesN<32> {
+    let mut preimage = Bytes::from_array(env, &option_index.to_be_bytes());
+    preimage.append(&salt.clone().into());
+    env.crypto().sha256(&preimage).to_bytes()
+}
+
+#[test]
+fn test_commit_reveal() {
+    std::println!("🧪 Test: Voto secreto con commit y reveal");
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(SimpleVoting, ());
+    let client = SimpleVotingClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let carol = Address::generate(&env);
+
+    client.init(&admin, &None);
+
+    let config = ProposalConfig {
+        end_time: Some(100),
+        reveal_end_time: Some(200),
+        ..si_no_config(&env)
+    };
+    let proposal_id = client.create_proposal(&creator, &config);
+
+    let alice_salt = BytesN::from_array(&env, &[1; 32]);
+    let bob_salt = BytesN::from_array(&env, &[2; 32]);
+    let carol_salt = BytesN::from_array(&env, &[3; 32]);
+
+    // Fase de compromiso: no se puede votar en claro y no se ve el recuento
+    assert_error(
+        &env,
+        client.try_vote_si(&alice, &proposal_id),
+        Error::SecretBallotMismatch,
+    );
+    client.commit(&alice, &proposal_id, &commitment(&env, 0, &alice_salt));
+    client.commit(&bob, &proposal_id, &commitment(&env, 1, &bob_salt));
+    client.commit(&carol, &proposal_id, &commitment(&env, 1, &carol_salt));
+    assert_eq!(client.get_results(&proposal_id).votes, vec![&env, 0, 0]);
+
+    // Todavía no se puede revelar
+    assert_error(
+        &env,
+        client.try_reveal(&alice, &proposal_id, &0, &alice_salt),
+        Error::RevealNotActive,
+    );
+
+    // Fase de revelado
+    env.ledger().set_timestamp(150);
+    assert_error(
+        &env,
+        client.try_commit(&alice, &proposal_id, &commitment(&env, 1, &alice_salt)),
+        Error::VotingNotActive,
+    );
+    assert_error(
+        &env,
+        client.try_reveal(&bob, &proposal_id, &0, &bob_salt),
+        Error::InvalidReveal,
+    );
+    client.reveal(&alice, &proposal_id, &0, &alice_salt);
+    client.reveal(&bob, &proposal_id, &1, &bob_salt);
+
+    // carol no revela a tiempo: su voto no cuenta
+    env.ledger().set_timestamp(200);
+    assert_error(
+        &env,
+        client.try_reveal(&carol, &proposal_id, &1, &carol_salt),
+        Error::RevealNotActive,
+    );
+
+    assert_eq!(client.get_results(&proposal_id).votes, vec![&env, 1, 1]);
+    assert!(client.has_voted(&alice, &proposal_id));
+    assert!(!client.has_voted(&carol, &proposal_id));
+
+    // La fase de revelado debe terminar después de la de compromiso
+    let invalid = ProposalConfig {
+        reveal_end_time: Some(100),
+        ..config
+    };
+    assert_error(
+        &env,
+        client.try_create_proposal(&creator, &invalid),
+        Error::InvalidWindow,
+    );
+}
+
+#[test]
+fn test_storage_ttl() {
+    std::println!("🧪 Test: Registros en almacenamiento persistente con TTL");
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(SimpleVoting, ());
+    let client = SimpleVotingClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let voter = Address::generate(&env);
+
+    client.init(&admin, &None);
+    let proposal_id = client.create_proposal(&creator, &si_no_config(&env));
+    client.vote_si(&voter, &proposal_id);
+    let ranked_id = client.create_proposal(
+        &creator,
+        &ProposalConfig {
+            ranked: true,
+            ..si_no_config(&env)
+        },
+    );
+    client.vote_ranked(&voter, &ranked_id, &vec![&env, 1, 0]);
+
+    let has_voted_key = DataKey::HasVoted(proposal_id, voter.clone());
+    env.as_contract(&contract_id, || {
+        // El registro del votante no está en la instancia
+        assert!(!env.storage().instance().has(&has_voted_key));
+        assert!(env.storage().persistent().has(&has_voted_key));
+        assert_eq!(
+            env.storage().persistent().get_ttl(&has_voted_key),
+            storage::PERSISTENT_BUMP_AMOUNT
+        );
+    });
+
+    // Avanzar el ledger sin dejar caducar la instancia (su TTL es más corto que el de
+    // las entradas persistentes) y extender con bump
+    env.ledger().with_mut(|ledger| {
+        ledger.sequence_number += storage::INSTANCE_LIFETIME_THRESHOLD - 10;
+    });
+    env.as_contract(&contract_id, || {
+        assert!(
+            env.storage().persistent().get_ttl(&has_voted_key)
+                < storage::PERSISTENT_LIFETIME_THRESHOLD
+        );
+        assert!(env.storage().instance().get_ttl() < storage::INSTANCE_LIFETIME_THRESHOLD);
+    });
+    client.bump(&proposal_id, &vec![&env, voter.clone()]);
+
+    env.as_contract(&contract_id, || {
+        assert_eq!(
+            env.storage().persistent().get_ttl(&has_voted_key),
+            storage::PERSISTENT_BUMP_AMOUNT
+        );
+        assert_eq!(
+            env.storage()
+                .persistent()
+                .get_ttl(&DataKey::Config(proposal_id)),
+            storage::PERSISTENT_BUMP_AMOUNT
+        );
+        assert_eq!(
+            env.storage().instance().get_ttl(),
+            storage::INSTANCE_BUMP_AMOUNT
+        );
+    });
+
+    assert!(client.has_voted(&voter, &proposal_id));
+
+    // El índice de votantes por ranking se extiende por páginas
+    let ranked_voter_key = DataKey::RankedVoter(ranked_id, 0);
+    let ranking_key = DataKey::Ranking(ranked_id, voter.clone());
+    env.as_contract(&contract_id, || {
+        assert!(
+            env.storage().persistent().get_ttl(&ranked_voter_key)
+                < storage::PERSISTENT_LIFETIME_THRESHOLD
+        );
+    });
+    assert_error(
+        &env,
+        client.try_bump_ranked_voters(&proposal_id, &0, &10),
+        Error::RankedBallotMismatch,
+    );
+    client.bump_ranked_voters(&ranked_id, &0, &10);
+    env.as_contract(&contract_id, || {
+        assert_eq!(
+            env.storage().persistent().get_ttl(&ranked_voter_key),
+            storage::PERSISTENT_BUMP_AMOUNT
+        );
+        assert_eq!(
+            env.storage().persistent().get_ttl(&ranking_key),
+            storage::PERSISTENT_BUMP_AMOUNT
+        );
+    });
+
+    // Y también el registro de votos
+    let log_key = DataKey::VoterLog(proposal_id, 0);
+    env.as_contract(&contract_id, || {
+        assert!(
+            env.storage().persistent().get_ttl(&log_key) < storage::PERSISTENT_LIFETIME_THRESHOLD
+        );
+    });
+    client.bump_voter_log(&proposal_id, &0, &10);
+    env.as_contract(&contract_id, || {
+        assert_eq!(
+            env.storage().persistent().get_ttl(&log_key),
+            storage::PERSISTENT_BUMP_AMOUNT
+        );
+    });
+}
+
+#[test]
+fn test_admin_transfer_and_roles() {
+    std::println!("🧪 Test: Transferencia de administrador y roles");
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(SimpleVoting, ());
+    let client = SimpleVotingClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let new_admin = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let closer = Address::generate(&env);
+    let moderator = Address::generate(&env);
+    let voter = Address::generate(&env);
+
+    client.init(&admin, &None);
+
+    // Transferencia en dos pasos
+    client.propose_admin(&admin, &new_admin);
+    assert_eq!(client.get_admin(), admin);
+    assert_error(
+        &env,
+        client.try_accept_admin(&creator),
+        Error::NoPendingAdmin,
+    );
+    client.accept_admin(&new_admin);
+    assert_eq!(client.get_admin(), new_admin);
+    assert_error(
+        &env,
+        client.try_propose_admin(&admin, &creator),
+        Error::NotAdmin,
+    );
+
+    // Roles
+    client.grant_role(&new_admin, &closer, &Role::Closer);
+    client.grant_role(&new_admin, &moderator, &Role::Moderator);
+    assert!(client.has_role(&closer, &Role::Closer));
+    assert!(!client.has_role(&closer, &Role::Moderator));
+
+    let config = ProposalConfig {
+        restricted: true,
+        ..si_no_config(&env)
+    };
+    let proposal_id = client.create_proposal(&creator, &config);
+
+    // El moderador gestiona la lista pero no puede cerrar
+    client.add_voter(&moderator, &proposal_id, &voter);
+    assert_error(
+        &env,
+        client.try_close_voting(&moderator, &proposal_id),
+        Error::Unauthorized,
+    );
+
+    // El closer puede cerrar pero no gestionar la lista
+    assert_error(
+        &env,
+        client.try_remove_voter(&closer, &proposal_id, &voter),
+        Error::Unauthorized,
+    );
+    client.close_voting(&closer, &proposal_id);
+    assert_eq!(client.get_results(&proposal_id).state, BallotState::Closed);
+
+    // Sin el rol ya no puede cerrar otras propuestas
+    client.revoke_role(&new_admin, &closer, &Role::Closer);
+    let other = client.create_proposal(&creator, &si_no_config(&env));
+    assert_error(
+        &env,
+        client.try_close_voting(&closer, &other),
+        Error::Unauthorized,
+    );
+
+    // El administrador puede cerrar cualquier propuesta
+    client.close_voting(&new_admin, &other);
+}
+
+#[test]
+fn test_ballot_lifecycle() {
+    std::println!("🧪 Test: Estados de la propuesta");
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(SimpleVoting, ());
+    let client = SimpleVotingClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let voter = Address::generate(&env);
+
+    client.init(&admin, &None);
+
+    let config = ProposalConfig {
+        draft: true,
+        ..si_no_config(&env)
+    };
+    let proposal_id = client.create_proposal(&creator, &config);
+    assert_eq!(client.get_results(&proposal_id).state, BallotState::Draft);
+
+    // En borrador no se vota ni se puede cerrar
+    assert_error(
+        &env,
+        client.try_vote_si(&voter, &proposal_id),
+        Error::VotingNotActive,
+    );
+    assert_error(
+        &env,
+        client.try_close_voting(&creator, &proposal_id),
+        Error::InvalidTransition,
+    );
+
+    client.open_proposal(&creator, &proposal_id);
+    client.vote_si(&voter, &proposal_id);
+
+    // Pausa por incidente
+    client.pause(&creator, &proposal_id);
+    assert_eq!(client.get_results(&proposal_id).state, BallotState::Paused);
+    assert_error(
+        &env,
+        client.try_vote_no(&Address::generate(&env), &proposal_id),
+        Error::VotingNotActive,
+    );
+    assert_error(
+        &env,
+        client.try_pause(&creator, &proposal_id),
+        Error::InvalidTransition,
+    );
+    client.resume(&creator, &proposal_id);
+
+    // Cerrar y reabrir descarta el resultado
+    client.close_voting(&creator, &proposal_id);
+    assert_eq!(client.get_outcome(&proposal_id), Outcome::Passed(0));
+    client.reopen(&creator, &proposal_id);
+    assert_eq!(client.get_results(&proposal_id).state, BallotState::Open);
+    assert_eq!(client.get_outcome(&proposal_id), Outcome::Pending);
+
+    // Cancelar
+    client.cancel(&creator, &proposal_id);
+    assert_eq!(
+        client.get_results(&proposal_id).state,
+        BallotState::Cancelled
+    );
+    assert_eq!(client.get_outcome(&proposal_id), Outcome::Cancelled);
+    assert_error(
+        &env,
+        client.try_reopen(&creator, &proposal_id),
+        Error::InvalidTransition,
+    );
+    assert_error(
+        &env,
+        client.try_resume(&creator, &proposal_id),
+        Error::InvalidTransition,
+    );
+
+    // Terminada la ventana ya no se puede cancelar, pausar ni reanudar: solo cerrar
+    let now = env.ledger().timestamp();
+    let timed_config = ProposalConfig {
+        end_time: Some(now + 100),
+        ..si_no_config(&env)
+    };
+    let timed = client.create_proposal(&creator, &timed_config);
+    let paused = client.create_proposal(&creator, &timed_config);
+    client.vote_si(&voter, &timed);
+    client.pause(&creator, &paused);
+    env.ledger().with_mut(|li| li.timestamp = now + 200);
+    assert_eq!(client.get_results(&timed).state, BallotState::Closed);
+    assert_error(
+        &env,
+        client.try_cancel(&creator, &timed),
+        Error::InvalidTransition,
+    );
+    assert_error(
+        &env,
+        client.try_pause(&creator, &timed),
+        Error::InvalidTransition,
+    );
+    assert_error(
+        &env,
+        client.try_resume(&creator, &paused),
+        Error::InvalidTransition,
+    );
+    assert_error(
+        &env,
+        client.try_cancel(&creator, &paused),
+        Error::InvalidTransition,
+    );
+    client.close_voting(&creator, &timed);
+    assert_eq!(client.get_outcome(&timed), Outcome::Passed(0));
+}
+
+#[test]
+fn test_upgrade() {
+    std::println!("🧪 Test: Actualizar el código del contrato");
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(SimpleVoting, ());
+    let client = SimpleVotingClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let creator = Address::generate(&env);
+    client.init(&admin, &None);
+    assert_eq!(client.version(), SCHEMA_VERSION);
+
+    let proposal_id = client.create_proposal(&creator, &si_no_config(&env));
+
+    // Segunda versión compilada del contrato (ver test_fixtures/upgrade_v2.wat)
+    let new_wasm_hash = env
+        .deployer()
+        .upload_contract_wasm(include_bytes!("../test_fixtures/upgrade_v2.wasm").as_slice());
+
+    // Solo el administrador puede actualizar
+    client.upgrade(&new_wasm_hash);
+    assert_eq!(
+        env.auths()[0].0,
+        admin,
+        "upgrade debe requerir la firma del administrador"
+    );
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol_short!("upgrade"), admin.clone()).into_val(&env),
+                new_wasm_hash.into_val(&env),
+            ),
+        ]
+    );
+
+    // Ahora responde el código nuevo y el almacenamiento sigue ahí
+    let version: u32 = env.invoke_contract(&contract_id, &Symbol::new(&env, "version"), vec![&env]);
+    assert_eq!(version, 2);
+    env.as_contract(&contract_id, || {
+        assert!(env.storage().instance().has(&DataKey::Admin));
+        assert!(env
+            .storage()
+            .persistent()
+            .has(&DataKey::Config(proposal_id)));
+    });
+}
+
+#[test]
+fn test_migrate_legacy_storage() {
+    std::println!("🧪 Test: Migrar el almacenamiento del contrato original");
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(SimpleVoting, ());
+    let client = SimpleVotingClient::new(&env, &contract_id);
+
+    let creator = Address::generate(&env);
+    let voter_si = Address::generate(&env);
+    let voter_no = Address::generate(&env);
+    let voter_other = Address::generate(&env);
+
+    // Datos tal como los dejaba el contrato original, ya cerrado
+    env.as_contract(&contract_id, || {
+        let instance = env.storage().instance();
+        instance.set(&LegacyDataKey::Creator, &creator);
+        instance.set(&LegacyDataKey::Active, &false);
+        instance.set(&LegacyDataKey::VotesSi, &2u32);
+        instance.set(&LegacyDataKey::VotesNo, &1u32);
+        instance.set(&LegacyDataKey::HasVoted(voter_si.clone()), &true);
+        instance.set(&LegacyDataKey::HasVoted(voter_no.clone()), &true);
+        instance.set(&LegacyDataKey::HasVoted(voter_other.clone()), &true);
+    });
+    assert_eq!(client.version(), 0);
+
+    // No se puede inicializar encima de datos antiguos
+    assert_error(
+        &env,
+        client.try_init(&Address::generate(&env), &None),
+        Error::AlreadyInitialized,
+    );
+
+    // Primer lote: lo autoriza el creador original, que pasa a ser administrador
+    assert_eq!(
+        client.migrate(&vec![&env, voter_si.clone(), voter_no.clone()]),
+        2
+    );
+    assert_eq!(env.auths()[0].0, creator);
+    assert_eq!(client.version(), SCHEMA_VERSION);
+    assert_eq!(client.get_admin(), creator);
+    assert_eq!(client.list_proposals(), vec![&env, 0]);
+
+    let results = client.get_results(&0);
+    assert_eq!(results.votes, vec![&env, 2, 1]);
+    assert_eq!(results.state, BallotState::Closed);
+    assert_eq!(client.get_outcome(&0), Outcome::Passed(0));
+    assert_eq!(
+        client.get_vote(&voter_si, &0),
+        Some(VoteRecord {
+            option: UNKNOWN_OPTION,
+            weight: 1,
+        })
+    );
+    // Quien aún no se migró sigue constando como votante
+    assert!(client.has_voted(&voter_other, &0));
+
+    // Segundo lote: lo autoriza el administrador; repetir direcciones no cuenta dos veces
+    assert_eq!(
+        client.migrate(&vec![&env, voter_other.clone(), voter_si.clone()]),
+        1
+    );
+    assert_eq!(env.auths()[0].0, creator);
+    assert!(client.has_voted(&voter_other, &0));
+
+    env.as_contract(&contract_id, || {
+        let instance = env.storage().instance();
+        assert!(!instance.has(&LegacyDataKey::Creator));
+        assert!(!instance.has(&LegacyDataKey::VotesSi));
+        assert!(!instance.has(&LegacyDataKey::HasVoted(voter_other.clone())));
+    });
+
+    // Un almacenamiento de una versión futura no se toca
+    env.as_contract(&contract_id, || {
+        env.storage()
+            .instance()
+            .set(&DataKey::Version, &(SCHEMA_VERSION + 1));
+    });
+    assert_error(
+        &env,
+        client.try_migrate(&vec![&env]),
+        Error::UnsupportedVersion,
+    );
+
+    // Una votación original todavía abierta sigue abierta tras migrar, pero quien ya votó
+    // no puede repetir aunque su voto no se haya movido en el primer lote
+    let open_id = env.register(SimpleVoting, ());
+    let open = SimpleVotingClient::new(&env, &open_id);
+    env.as_contract(&open_id, || {
+        let instance = env.storage().instance();
+        instance.set(&LegacyDataKey::Creator, &creator);
+        instance.set(&LegacyDataKey::Active, &true);
+        instance.set(&LegacyDataKey::VotesSi, &1u32);
+        instance.set(&LegacyDataKey::VotesNo, &0u32);
+        instance.set(&LegacyDataKey::HasVoted(voter_si.clone()), &true);
+    });
+    assert_eq!(open.migrate(&vec![&env]), 0);
+    assert_eq!(open.get_results(&0).state, BallotState::Open);
+    assert_eq!(
+        open.get_vote(&voter_si, &0),
+        Some(VoteRecord {
+            option: UNKNOWN_OPTION,
+            weight: 1,
+        })
+    );
+    assert_error(&env, open.try_vote_si(&voter_si, &0), Error::AlreadyVoted);
+    assert_error(
+        &env,
+        open.try_delegate(&voter_si, &0, &voter_no),
+        Error::AlreadyVoted,
+    );
+    assert_error(
+        &env,
+        open.try_delegate(&voter_no, &0, &voter_si),
+        Error::DelegateAlreadyVoted,
+    );
+
+    // Los votantes nuevos sí pueden votar, y los migrados después siguen sin repetir
+    open.vote_no(&voter_no, &0);
+    assert_eq!(open.migrate(&vec![&env, voter_si.clone()]), 1);
+    assert_error(&env, open.try_vote_no(&voter_si, &0), Error::AlreadyVoted);
+    assert_eq!(open.get_results(&0).votes, vec![&env, 1, 1]);
+}
+
+#[test]
+fn test_committee_close() {
+    std::println!("🧪 Test: Cierre aprobado por un comité");
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(SimpleVoting, ());
+    let client = SimpleVotingClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let voter = Address::generate(&env);
+    let signers = vec![
+        &env,
+        Address::generate(&env),
+        Address::generate(&env),
+        Address::generate(&env),
+    ];
+
+    client.init(&admin, &None);
+
+    // El umbral tiene que estar entre 1 y el número de firmantes, sin repetidos
+    for (committee, committee_threshold) in [
+        (signers.clone(), 0),
+        (signers.clone(), 4),
+        (vec![&env, creator.clone(), creator.clone()], 1),
+        (vec![&env], 1),
+    ] {
+        let config = ProposalConfig {
+            committee,
+            committee_threshold,
+            ..si_no_config(&env)
+        };
+        assert_error(
+            &env,
+            client.try_create_proposal(&creator, &config),
+            Error::InvalidCommittee,
+        );
+    }
+
+    let config = ProposalConfig {
+        committee: signers.clone(),
+        committee_threshold: 2,
+        ..si_no_config(&env)
+    };
+    let proposal_id = client.create_proposal(&creator, &config);
+    client.vote_si(&voter, &proposal_id);
+
+    // Ni el creador ni el administrador pueden cerrarla solos
+    assert_error(
+        &env,
+        client.try_close_voting(&creator, &proposal_id),
+        Error::CommitteeRequired,
+    );
+    assert_error(
+        &env,
+        client.try_close_voting(&admin, &proposal_id),
+        Error::CommitteeRequired,
+    );
+    assert_error(
+        &env,
+        client.try_approve_close(&voter, &proposal_id),
+        Error::Unauthorized,
+    );
+
+    // Primera aprobación: sigue abierta
+    client.approve_close(&signers.get(0).unwrap(), &proposal_id);
+    assert_eq!(
+        client.get_close_approvals(&proposal_id),
+        vec![&env, signers.get(0).unwrap()]
+    );
+    assert_error(
+        &env,
+        client.try_approve_close(&signers.get(0).unwrap(), &proposal_id),
+        Error::AlreadyApproved,
+    );
+    assert_eq!(client.get_results(&proposal_id).state, BallotState::Open);
+
+    // La segunda alcanza el umbral y cierra
+    client.approve_close(&signers.get(2).unwrap(), &proposal_id);
+    assert_eq!(client.get_results(&proposal_id).state, BallotState::Closed);
+    assert_eq!(client.get_outcome(&proposal_id), Outcome::Passed(0));
+    assert_error(
+        &env,
+        client.try_approve_close(&signers.get(1).unwrap(), &proposal_id),
+        Error::InvalidTransition,
+    );
+
+    // Al reabrir hay que volver a reunir las aprobaciones
+    client.reopen(&creator, &proposal_id);
+    assert_eq!(client.get_close_approvals(&proposal_id), vec![&env]);
+
+    // Sin comité no hay aprobaciones que dar
+    let plain = client.create_proposal(&creator, &si_no_config(&env));
+    assert_error(
+        &env,
+        client.try_approve_close(&signers.get(0).unwrap(), &plain),
+        Error::NoCommittee,
+    );
+}
+
+#[test]
+fn test_ranked_choice() {
+    std::println!("🧪 Test: Votación por ranking con segunda vuelta instantánea");
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(SimpleVoting, ());
+    let client = SimpleVotingClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let creator = Address::generate(&env);
+    client.init(&admin, &None);
+
+    let config = ProposalConfig {
+        options: vec![
+            &env,
+            String::from_str(&env, "A"),
+            String::from_str(&env, "B"),
+            String::from_str(&env, "C"),
+        ],
+        ranked: true,
+        ..si_no_config(&env)
+    };
+
+    // El recuento por ranking no admite voto secreto ni cambiar el voto
+    let invalid = ProposalConfig {
+        allow_vote_change: true,
+        ..config.clone()
+    };
+    assert_error(
+        &env,
+        client.try_create_proposal(&creator, &invalid),
+        Error::RankedBallotMismatch,
+    );
+
+    let proposal_id = client.create_proposal(&creator, &config);
+    let voter = Address::generate(&env);
+
+    // Cada tipo de propuesta se vota con su función
+    assert_error(
+        &env,
+        client.try_vote(&voter, &proposal_id, &0),
+        Error::RankedBallotMismatch,
+    );
+    let single = client.create_proposal(&creator, &si_no_config(&env));
+    assert_error(
+        &env,
+        client.try_vote_ranked(&voter, &single, &vec![&env, 0]),
+        Error::RankedBallotMismatch,
+    );
+    assert_error(
+        &env,
+        client.try_tally_irv(&single),
+        Error::RankedBallotMismatch,
+    );
+
+    // Rankings vacíos, con repetidas o con opciones que no existen
+    for ranking in [vec![&env], vec![&env, 0, 0], vec![&env, 1, 3]] {
+        assert_error(
+            &env,
+            client.try_vote_ranked(&voter, &proposal_id, &ranking),
+            Error::InvalidRanking,
+        );
+    }
+
+    // Primera ronda: A 3, B 2, C 1; se elimina C y su voto pasa a B
+    for ranking in [
+        vec![&env, 0],
+        vec![&env, 0, 2],
+        vec![&env, 1],
+        vec![&env, 1, 0],
+        vec![&env, 2, 1],
+    ] {
+        client.vote_ranked(&Address::generate(&env), &proposal_id, &ranking);
+    }
+    client.vote_ranked(&voter, &proposal_id, &vec![&env, 0]);
+    assert_error(
+        &env,
+        client.try_vote_ranked(&voter, &proposal_id, &vec![&env, 1]),
+        Error::AlreadyVoted,
+    );
+
+    // `get_results` muestra las primeras preferencias; el recuento se hace al cerrar
+    assert_eq!(client.get_results(&proposal_id).votes, vec![&env, 3, 2, 1]);
+    assert_eq!(
+        client.tally_irv(&proposal_id),
+        IrvTally {
+            winner: None,
+            rounds: vec![&env],
+        }
+    );
+
+    // En la segunda ronda A y B empatan a 3: no hay ganador
+    client.close_voting(&creator, &proposal_id);
+    assert_eq!(client.get_outcome(&proposal_id), Outcome::Rejected);
+    let tally = client.tally_irv(&proposal_id);
+    assert_eq!(
+        tally.rounds,
+        vec![&env, vec![&env, 3, 2, 1], vec![&env, 3, 3, 0]]
+    );
+    assert_eq!(tally.winner, None);
+
+    // Sin el voto de más para A, B gana en la segunda ronda
+    let proposal_id = client.create_proposal(&creator, &config);
+    for ranking in [
+        vec![&env, 0],
+        vec![&env, 0, 2],
+        vec![&env, 1],
+        vec![&env, 1, 0],
+        vec![&env, 2, 1],
+    ] {
+        client.vote_ranked(&Address::generate(&env), &proposal_id, &ranking);
+    }
+    client.close_voting(&creator, &proposal_id);
+    assert_eq!(client.get_outcome(&proposal_id), Outcome::Passed(1));
+    let tally = client.tally_irv(&proposal_id);
+    assert_eq!(
+        tally.rounds,
+        vec![&env, vec![&env, 2, 2, 1], vec![&env, 2, 3, 0]]
+    );
+    assert_eq!(tally.winner, Some(1));
+
+    // Si al eliminar las empatadas en último lugar solo queda una, gana esa
+    let survivor = client.create_proposal(&creator, &config);
+    for ranking in [vec![&env, 0], vec![&env, 0], vec![&env, 1], vec![&env, 2]] {
+        client.vote_ranked(&Address::generate(&env), &survivor, &ranking);
+    }
+    client.close_voting(&creator, &survivor);
+    assert_eq!(client.get_outcome(&survivor), Outcome::Passed(0));
+    let tally = client.tally_irv(&survivor);
+    assert_eq!(
+        tally.rounds,
+        vec![&env, vec![&env, 2, 1, 1], vec![&env, 2, 0, 0]]
+    );
+    assert_eq!(tally.winner, Some(0));
+
+    // Con más votos de los que caben en una página el recuento sigue tras cerrar
+    let paged = client.create_proposal(&creator, &config);
+    for (ranking, voters) in [
+        (vec![&env, 0, 1], 40),
+        (vec![&env, 1, 0], 35),
+        (vec![&env, 2, 1], 26),
+    ] {
+        for _ in 0..voters {
+            client.vote_ranked(&Address::generate(&env), &paged, &ranking);
+        }
+    }
+    assert_error(
+        &env,
+        client.try_tally_ranked(&paged, &10),
+        Error::NoPendingTally,
+    );
+
+    client.close_voting(&creator, &paged);
+    assert_eq!(client.get_outcome(&paged), Outcome::Pending);
+    assert_eq!(client.tally_ranked(&paged, &1), Outcome::Pending);
+    // La primera ronda ya está contada
+    assert_eq!(
+        client.tally_irv(&paged),
+        IrvTally {
+            winner: None,
+            rounds: vec![&env, vec![&env, 40, 35, 26]],
+        }
+    );
+    assert_eq!(
+        client.tally_ranked(&paged, &MAX_PAGE_SIZE),
+        Outcome::Pending
+    );
+    assert_eq!(
+        client.tally_ranked(&paged, &MAX_PAGE_SIZE),
+        Outcome::Passed(1)
+    );
+    assert_eq!(client.get_outcome(&paged), Outcome::Passed(1));
+    assert_eq!(
+        client.tally_irv(&paged),
+        IrvTally {
+            winner: Some(1),
+            rounds: vec![&env, vec![&env, 40, 35, 26], vec![&env, 40, 61, 0]],
+        }
+    );
+    assert_error(
+        &env,
+        client.try_tally_ranked(&paged, &10),
+        Error::NoPendingTally,
+    );
+}
+
+#[test]
+fn test_quadratic_voting() {
+    std::println!("🧪 Test: Votación cuadrática con créditos");
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(SimpleVoting, ());
+    let client = SimpleVotingClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    client.init(&admin, &None);
+
+    let config = ProposalConfig {
+        credits: 10,
+        restricted: true,
+        ..si_no_config(&env)
+    };
+    let invalid = ProposalConfig {
+        ranked: true,
+        ..config.clone()
+    };
+    assert_error(
+        &env,
+        client.try_create_proposal(&creator, &invalid),
+        Error::QuadraticBallotMismatch,
+    );
+
+    // Sin lista de votantes los créditos se multiplicarían con direcciones nuevas
+    let open = ProposalConfig {
+        restricted: false,
+        ..config.clone()
+    };
+    assert_error(
+        &env,
+        client.try_create_proposal(&creator, &open),
+        Error::QuadraticBallotMismatch,
+    );
+
+    let proposal_id = client.create_proposal(&creator, &config);
+    assert_eq!(client.remaining_credits(&alice, &proposal_id), 0);
+    client.add_voters(
+        &creator,
+        &proposal_id,
+        &vec![&env, alice.clone(), bob.clone()],
+    );
+    assert_eq!(client.remaining_credits(&alice, &proposal_id), 10);
+    assert!(!client.has_voted(&alice, &proposal_id));
+
+    // En una propuesta cuadrática no se vota ni se delega de la forma normal
+    assert_error(
+        &env,
+        client.try_vote_si(&alice, &proposal_id),
+        Error::QuadraticBallotMismatch,
+    );
+    assert_error(
+        &env,
+        client.try_delegate(&alice, &proposal_id, &bob),
+        Error::QuadraticBallotMismatch,
+    );
+
+    // 2 votos a Si cuestan 4; pasar a 3 cuesta 5 más
+    client.vote_quadratic(&alice, &proposal_id, &0, &2);
+    assert_eq!(client.remaining_credits(&alice, &proposal_id), 6);
+    client.vote_quadratic(&alice, &proposal_id, &0, &1);
+    assert_eq!(client.remaining_credits(&alice, &proposal_id), 1);
+    assert!(client.has_voted(&alice, &proposal_id));
+
+    // El crédito que queda solo alcanza para 1 voto a No
+    assert_error(
+        &env,
+        client.try_vote_quadratic(&alice, &proposal_id, &0, &1),
+        Error::InsufficientCredits,
+    );
+    assert_error(
+        &env,
+        client.try_vote_quadratic(&alice, &proposal_id, &1, &2),
+        Error::InsufficientCredits,
+    );
+    client.vote_quadratic(&alice, &proposal_id, &1, &1);
+    assert_eq!(client.remaining_credits(&alice, &proposal_id), 0);
+
+    // El reparto se consulta por opción; no hay un único voto que devolver
+    assert_eq!(
+        client.get_quadratic_votes(&alice, &proposal_id),
+        vec![&env, 3, 1]
+    );
+    assert_eq!(client.get_vote(&alice, &proposal_id), None);
+
+    // Bob gasta todo en No: 3 votos por 9 créditos
+    client.vote_quadratic(&bob, &proposal_id, &1, &3);
+    assert_eq!(client.remaining_credits(&bob, &proposal_id), 1);
+    assert_error(
+        &env,
+        client.try_vote_quadratic(&bob, &proposal_id, &1, &0),
+        Error::NoVotingPower,
+    );
+
+    assert_eq!(client.get_results(&proposal_id).votes, vec![&env, 3, 4]);
+    client.close_voting(&creator, &proposal_id);
+    assert_eq!(client.get_outcome(&proposal_id), Outcome::Passed(1));
+
+    // Fuera de las propuestas cuadráticas no hay créditos
+    let plain = client.create_proposal(&creator, &si_no_config(&env));
+    assert_error(
+        &env,
+        client.try_remaining_credits(&alice, &plain),
+        Error::QuadraticBallotMismatch,
+    );
+    assert_error(
+        &env,
+        client.try_vote_quadratic(&alice, &plain, &0, &1),
+        Error::QuadraticBallotMismatch,
+    );
+    assert_error(
+        &env,
+        client.try_get_quadratic_votes(&alice, &plain),
+        Error::QuadraticBallotMismatch,
+    );
+}
+
+#[test]
+fn test_list_voters() {
+    std::println!("🧪 Test: Registro de votos para auditoría");
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(SimpleVoting, ());
+    let client = SimpleVotingClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let carol = Address::generate(&env);
+    client.init(&admin, &None);
+
+    let config = ProposalConfig {
+        allow_vote_change: true,
+        ..si_no_config(&env)
+    };
+    let proposal_id = client.create_proposal(&creator, &config);
+    assert_eq!(client.voter_count(&proposal_id), 0);
+
+    env.ledger().with_mut(|li| li.sequence_number = 100);
+    client.vote_si(&alice, &proposal_id);
+    client.vote_no(&bob, &proposal_id);
+    env.ledger().with_mut(|li| li.sequence_number = 101);
+    client.change_vote(&alice, &proposal_id, &1);
+    client.vote_si(&carol, &proposal_id);
+    client.retract_vote(&bob, &proposal_id);
+
+    let entry = |voter: &Address, option: u32, weight: i128, ledger: u32| VoterEntry {
+        voter: voter.clone(),
+        option,
+        weight,
+        ledger,
+    };
+    assert_eq!(client.voter_count(&proposal_id), 6);
+    assert_eq!(
+        client.list_voters(&proposal_id, &0, &3),
+        vec![
+            &env,
+            entry(&alice, 0, 1, 100),
+            entry(&bob, 1, 1, 100),
+            entry(&alice, 0, -1, 101),
+        ]
+    );
+    let rest = client.list_voters(&proposal_id, &3, &10);
+    assert_eq!(
+        rest,
+        vec![
+            &env,
+            entry(&alice, 1, 1, 101),
+            entry(&carol, 0, 1, 101),
+            entry(&bob, 1, -1, 101),
+        ]
+    );
+    assert_eq!(client.list_voters(&proposal_id, &6, &10), vec![&env]);
+
+    // Sumando el registro se reconstruye el recuento
+    let mut tally = [0i128; 2];
+    for entry in client.list_voters(&proposal_id, &0, &MAX_PAGE_SIZE).iter() {
+        tally[entry.option as usize] += entry.weight;
+    }
+    assert_eq!(
+        client.get_results(&proposal_id).votes,
+        vec![&env, tally[0], tally[1]]
+    );
+}
+
+#[test]
+fn test_proposal_metadata() {
+    std::println!("🧪 Test: Título, descripción y hash de la propuesta");
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(SimpleVoting, ());
+    let client = SimpleVotingClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let creator = Address::generate(&env);
+    client.init(&admin, &None);
+
+    let document = Bytes::from_slice(&env, "Presupuesto 2025: ...".as_bytes());
+    let config = ProposalConfig {
+        title: String::from_str(&env, "Presupuesto 2025"),
+        description: String::from_str(&env, "https://example.org/presupuesto-2025.pdf"),
+        content_hash: env.crypto().sha256(&document).to_bytes(),
+        ..si_no_config(&env)
+    };
+    let proposal_id = client.create_proposal(&creator, &config);
+
+    let proposal = client.get_proposal(&proposal_id);
+    assert_eq!(proposal.creator, creator);
+    assert_eq!(proposal.state, BallotState::Open);
+    assert_eq!(proposal.config, config);
+    assert_eq!(
+        proposal.config.content_hash,
+        env.crypto().sha256(&document).to_bytes()
+    );
+    assert_error(&env, client.try_get_proposal(&1), Error::ProposalNotFound);
+
+    // Límites de longitud
+    let long_title = ProposalConfig {
+        title: String::from_bytes(&env, &[b'a'; MAX_TITLE_LEN as usize + 1]),
+        ..si_no_config(&env)
+    };
+    assert_error(
+        &env,
+        client.try_create_proposal(&creator, &long_title),
+        Error::MetadataTooLong,
+    );
+    let long_description = ProposalConfig {
+        description: String::from_bytes(&env, &[b'a'; MAX_DESCRIPTION_LEN as usize + 1]),
+        ..si_no_config(&env)
+    };
+    assert_error(
+        &env,
+        client.try_create_proposal(&creator, &long_description),
+        Error::MetadataTooLong,
+    );
+
+    // Justo en el límite sí se acepta
+    let max_title = ProposalConfig {
+        title: String::from_bytes(&env, &[b'a'; MAX_TITLE_LEN as usize]),
+        ..si_no_config(&env)
+    };
+    client.create_proposal(&creator, &max_title);
+}
+
+#[test]
+fn test_deposit_and_vote_fee() {
+    std::println!("🧪 Test: Depósito por propuesta y tarifa por voto");
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(SimpleVoting, ());
+    let client = SimpleVotingClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let voter = Address::generate(&env);
+    let treasury = Address::generate(&env);
+
+    let token = env.register_stellar_asset_contract_v2(admin.clone());
+    let token_admin = StellarAssetClient::new(&env, &token.address());
+    let token_client = TokenClient::new(&env, &token.address());
+    token_admin.mint(&creator, &300);
+    token_admin.mint(&voter, &10);
+
+    client.init(&admin, &None);
+    assert_error(&env, client.try_get_fees(), Error::NoFees);
+
+    let fees = FeeConfig {
+        token: token.address(),
+        deposit: 100,
+        vote_fee: 2,
+        treasury: treasury.clone(),
+    };
+    assert_error(
+        &env,
+        client.try_set_fees(
+            &admin,
+            &FeeConfig {
+                deposit: -1,
+                ..fees.clone()
+            },
+        ),
+        Error::InvalidFee,
+    );
+    assert_error(&env, client.try_set_fees(&creator, &fees), Error::NotAdmin);
+    client.set_fees(&admin, &fees);
+    assert_eq!(client.get_fees(), fees);
+
+    // Crear una propuesta bloquea el depósito
+    let passed = client.create_proposal(&creator, &si_no_config(&env));
+    assert_eq!(client.get_deposit(&passed), 100);
+    assert_eq!(token_client.balance(&creator), 200);
+    assert_eq!(token_client.balance(&contract_id), 100);
+
+    // Cada voto paga la tarifa a la tesorería
+    client.vote_si(&voter, &passed);
+    assert_eq!(token_client.balance(&voter), 8);
+    assert_eq!(token_client.balance(&treasury), 2);
+
+    // Con participación suficiente se devuelve al cerrar
+    client.close_voting(&creator, &passed);
+    assert_eq!(client.get_deposit(&passed), 0);
+    assert_eq!(token_client.balance(&creator), 300);
+    assert_eq!(token_client.balance(&contract_id), 0);
+
+    // Sin quórum se retiene en la tesorería
+    let empty = client.create_proposal(&creator, &si_no_config(&env));
+    client.close_voting(&creator, &empty);
+    assert_eq!(client.get_outcome(&empty), Outcome::QuorumNotMet);
+    assert_eq!(token_client.balance(&creator), 200);
+    assert_eq!(token_client.balance(&treasury), 102);
+
+    // Al cancelar también se retiene, y reabrir no lo cobra otra vez
+    let cancelled = client.create_proposal(&creator, &si_no_config(&env));
+    client.cancel(&admin, &cancelled);
+    assert_eq!(token_client.balance(&treasury), 202);
+    client.reopen(&creator, &passed);
+    client.close_voting(&creator, &passed);
+    assert_eq!(token_client.balance(&creator), 100);
+
+    // Sin tarifas no se cobra nada
+    client.remove_fees(&admin);
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol_short!("fees_rm"), admin.clone()).into_val(&env),
+                ().into_val(&env),
+            ),
+        ]
+    );
+    let free = client.create_proposal(&creator, &si_no_config(&env));
+    assert_eq!(client.get_deposit(&free), 0);
+    client.vote_si(&voter, &free);
+    assert_eq!(token_client.balance(&voter), 8);
+
+    // Con el mismo token como token de gobernanza, votar bloquea todo el balance y la
+    // tarifa no se podría pagar: solo se admite el depósito
+    let weighted_id = env.register(SimpleVoting, ());
+    let weighted = SimpleVotingClient::new(&env, &weighted_id);
+    weighted.init(&admin, &Some(token.address()));
+    assert_error(
+        &env,
+        weighted.try_set_fees(&admin, &fees),
+        Error::InvalidFee,
+    );
+    weighted.set_fees(
+        &admin,
+        &FeeConfig {
+            vote_fee: 0,
+            ..fees.clone()
+        },
+    );
+    let holder = Address::generate(&env);
+    token_admin.mint(&holder, &50);
+    let weighted_proposal = weighted.create_proposal(&creator, &si_no_config(&env));
+    weighted.vote_si(&holder, &weighted_proposal);
+    assert_eq!(
+        weighted.get_results(&weighted_proposal).votes,
+        vec![&env, 50, 0]
+    );
+}
+
+mod target {
+    use soroban_sdk::{contract, contractimpl, symbol_short, Env};
+
+    /// Contrato de prueba que guarda el último valor y cuántas veces se le llamó
+    #[contract]
+    pub struct Target;
+
+    #[contractimpl]
+    impl Target {
+        pub fn set_value(env: Env, value: u32) {
+            let calls: u32 = env
+                .storage()
+                .instance()
+                .get(&symbol_short!("calls"))
+                .unwrap_or(0);
+            env.storage()
+                .instance()
+                .set(&symbol_short!("calls"), &(calls + 1));
+            env.storage()
+                .instance()
+                .set(&symbol_short!("value"), &value);
+        }
+
+        pub fn value(env: Env) -> (u32, u32) {
+            let value = env
+                .storage()
+                .instance()
+                .get(&symbol_short!("value"))
+                .unwrap_or(0);
+            let calls = env
+                .storage()
+                .instance()
+                .get(&symbol_short!("calls"))
+                .unwrap_or(0);
+            (value, calls)
+        }
+    }
+}
+
+#[test]
+fn test_execute_action() {
+    std::println!("🧪 Test: Ejecutar la acción de una propuesta aprobada");
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(SimpleVoting, ());
+    let client = SimpleVotingClient::new(&env, &contract_id);
+    let target_id = env.register(target::Target, ());
+    let target_client = target::TargetClient::new(&env, &target_id);
+
+    let admin = Address::generate(&env);
+    let creator = Address::generate(&env);
+    client.init(&admin, &None);
+
+    let config = ProposalConfig {
+        actions: vec![
+            &env,
+            ProposalAction {
+                contract: target_id.clone(),
+                function: Symbol::new(&env, "set_value"),
+                args: vec![&env, 42u32.into_val(&env)],
+            },
+        ],
+        ..si_no_config(&env)
+    };
+
+    // Sin acciones no hay nada que ejecutar
+    let plain = client.create_proposal(&creator, &si_no_config(&env));
+    assert_error(&env, client.try_execute(&plain), Error::NoActions);
+
+    // Mientras está abierta, o si gana el No, no se ejecuta
+    let rejected = client.create_proposal(&creator, &config);
+    client.vote_no(&Address::generate(&env), &rejected);
+    assert_error(&env, client.try_execute(&rejected), Error::NotPassed);
+    client.close_voting(&creator, &rejected);
+    assert_eq!(client.get_outcome(&rejected), Outcome::Passed(1));
+    assert_error(&env, client.try_execute(&rejected), Error::NotPassed);
+
+    // Aprobada: se ejecuta una sola vez
+    let proposal_id = client.create_proposal(&creator, &config);
+    client.vote_si(&Address::generate(&env), &proposal_id);
+    client.close_voting(&creator, &proposal_id);
+    assert!(!client.is_executed(&proposal_id));
+
+    // Incluso sin timelock hay que pasar por la cola
+    assert_error(&env, client.try_execute(&proposal_id), Error::NotQueued);
+    client.queue(&proposal_id);
+    client.execute(&proposal_id);
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol_short!("executed"), proposal_id).into_val(&env),
+                ().into_val(&env),
+            ),
+        ]
+    );
+    assert!(client.is_executed(&proposal_id));
+    assert_eq!(target_client.value(), (42, 1));
+
+    assert_error(
+        &env,
+        client.try_execute(&proposal_id),
+        Error::AlreadyExecuted,
+    );
+    assert_error(
+        &env,
+        client.try_reopen(&creator, &proposal_id),
+        Error::InvalidTransition,
+    );
+    assert_eq!(target_client.value(), (42, 1));
+}
+
+#[test]
+fn test_timelock() {
+    std::println!("🧪 Test: Timelock y veto de guardianes");
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(SimpleVoting, ());
+    let client = SimpleVotingClient::new(&env, &contract_id);
+    let target_id = env.register(target::Target, ());
+    let target_client = target::TargetClient::new(&env, &target_id);
+
+    let admin = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let guardian = Address::generate(&env);
+    client.init(&admin, &None);
+    client.grant_role(&admin, &guardian, &Role::Guardian);
+
+    let config = ProposalConfig {
+        actions: vec![
+            &env,
+            ProposalAction {
+                contract: target_id.clone(),
+                function: Symbol::new(&env, "set_value"),
+                args: vec![&env, 7u32.into_val(&env)],
+            },
+        ],
+        timelock: 3_600,
+        ..si_no_config(&env)
+    };
+
+    env.ledger().with_mut(|li| li.timestamp = 1_000);
+    let proposal_id = client.create_proposal(&creator, &config);
+    client.vote_si(&Address::generate(&env), &proposal_id);
+
+    // Antes de cerrar no se puede poner en cola
+    assert_error(&env, client.try_queue(&proposal_id), Error::NotPassed);
+    client.close_voting(&creator, &proposal_id);
+
+    // Con timelock no se ejecuta sin pasar por la cola
+    assert_error(&env, client.try_execute(&proposal_id), Error::NotQueued);
+    assert_eq!(client.get_queue_state(&proposal_id), QueueState::NotQueued);
+
+    assert_eq!(client.queue(&proposal_id), 4_600);
+    assert_eq!(
+        client.get_queue_state(&proposal_id),
+        QueueState::Queued(4_600)
+    );
+    assert_error(&env, client.try_queue(&proposal_id), Error::AlreadyQueued);
+    assert_error(
+        &env,
+        client.try_reopen(&creator, &proposal_id),
+        Error::InvalidTransition,
+    );
+
+    env.ledger().with_mut(|li| li.timestamp = 4_599);
+    assert_error(
+        &env,
+        client.try_execute(&proposal_id),
+        Error::TimelockNotExpired,
+    );
+
+    env.ledger().with_mut(|li| li.timestamp = 4_600);
+    client.execute(&proposal_id);
+    assert_eq!(client.get_queue_state(&proposal_id), QueueState::Executed);
+    assert_eq!(target_client.value(), (7, 1));
+    assert_error(&env, client.try_queue(&proposal_id), Error::AlreadyQueued);
+
+    // Otra propuesta aprobada que un guardián veta mientras espera
+    let vetoed = client.create_proposal(&creator, &config);
+    client.vote_si(&Address::generate(&env), &vetoed);
+    client.close_voting(&creator, &vetoed);
+    assert_error(
+        &env,
+        client.try_cancel_queued(&guardian, &vetoed),
+        Error::NotQueued,
+    );
+    client.queue(&vetoed);
+
+    // Ni el creador ni quien no tenga el rol pueden vetar
+    assert_error(
+        &env,
+        client.try_cancel_queued(&creator, &vetoed),
+        Error::Unauthorized,
+    );
+    client.cancel_queued(&guardian, &vetoed);
+    assert_eq!(client.get_queue_state(&vetoed), QueueState::Vetoed);
+    assert_error(
+        &env,
+        client.try_cancel_queued(&admin, &vetoed),
+        Error::Vetoed,
+    );
+
+    env.ledger().with_mut(|li| li.timestamp = 10_000);
+    assert_error(&env, client.try_execute(&vetoed), Error::Vetoed);
+    assert_error(&env, client.try_queue(&vetoed), Error::AlreadyQueued);
+    assert_eq!(target_client.value(), (7, 1));
+
+    // El creador no puede saltarse a los guardianes con `timelock: 0`: manda el mínimo
+    // que fija el administrador
+    assert_error(
+        &env,
+        client.try_set_min_timelock(&creator, &86_400),
+        Error::NotAdmin,
+    );
+    assert_error(
+        &env,
+        client.try_set_min_timelock(&admin, &(MAX_TIMELOCK + 1)),
+        Error::InvalidWindow,
+    );
+    assert_error(
+        &env,
+        client.try_create_proposal(
+            &creator,
+            &ProposalConfig {
+                timelock: u64::MAX,
+                ..config.clone()
+            },
+        ),
+        Error::InvalidWindow,
+    );
+    client.set_min_timelock(&admin, &86_400);
+    assert_eq!(
+        env.events().all(),
+        vec![
+            &env,
+            (
+                contract_id.clone(),
+                (symbol_short!("timelock"), admin.clone()).into_val(&env),
+                86_400u64.into_val(&env),
+            ),
+        ]
+    );
+    assert_eq!(client.get_min_timelock(), 86_400);
+
+    let hasty = client.create_proposal(
+        &creator,
+        &ProposalConfig {
+            timelock: 0,
+            ..config.clone()
+        },
+    );
+    client.vote_si(&Address::generate(&env), &hasty);
+    client.close_voting(&creator, &hasty);
+    assert_eq!(client.queue(&hasty), 96_400);
+    assert_error(&env, client.try_execute(&hasty), Error::TimelockNotExpired);
+    client.cancel_queued(&guardian, &hasty);
+    env.ledger().with_mut(|li| li.timestamp = 96_400);
+    assert_error(&env, client.try_execute(&hasty), Error::Vetoed);
+    assert_eq!(target_client.value(), (7, 1));
+}
+
+#[test]
+fn test_relayed_signed_votes() {
+    use ed25519_dalek::{Signer, SigningKey};
+
+    std::println!("🧪 Test: Votos firmados enviados por un relayer");
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register(SimpleVoting, ());
+    let client = SimpleVotingClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let creator = Address::generate(&env);
+    let relayer = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let alice_key = SigningKey::from_bytes(&[1; 32]);
+    let bob_key = SigningKey::from_bytes(&[2; 32]);
+
+    client.init(&admin, &None);
+    let proposal_id = client.create_proposal(&creator, &si_no_config(&env));
+
+    let sign = |key: &SigningKey, voter: &Address, proposal_id: u32, option: u32, nonce: u64| {
+        let message = client.vote_message(&proposal_id, &option, &nonce);
+        let signature = key.sign(&message.iter().collect::<std::vec::Vec<u8>>());
+        SignedVote {
+            voter: voter.clone(),
+            option,
+            nonce,
+            signature: BytesN::from_array(&env, &signature.to_bytes()),
+        }
+    };
+
+    // Sin clave registrada no se acepta la firma
+    assert_error(
+        &env,
+        client.try_relay_votes(
+            &relayer,
+            &proposal_id,
+            &vec![&env, sign(&alice_key, &alice, proposal_id, 0, 0)],
+        ),
+        Error::NoVoterKey,
+    );
+
+    client.register_voter_key(
+        &alice,
+        &BytesN::from_array(&env, &alice_key.verifying_key().to_bytes()),
+    );
+    client.register_voter_key(
+        &bob,
+        &BytesN::from_array(&env, &bob_key.verifying_key().to_bytes()),
+    );
+
+    // Un lote con dos votos
+    let alice_vote = sign(&alice_key, &alice, proposal_id, 0, 0);
+    let batch = vec![
+        &env,
+        alice_vote.clone(),
+        sign(&bob_key, &bob, proposal_id, 1, 0),
+    ];
+    assert_eq!(client.relay_votes(&relayer, &proposal_id, &batch), 2);
+    assert_eq!(env.auths()[0].0, relayer);
+    assert_eq!(client.get_results(&proposal_id).votes, vec![&env, 1, 1]);
+    assert!(client.has_voted(&alice, &proposal_id));
+    assert_eq!(client.get_nonce(&alice), 1);
+
+    // Repetir un voto firmado no sirve: el nonce ya se usó
+    let other = client.create_proposal(&creator, &si_no_config(&env));
+    assert_error(
+        &env,
+        client.try_relay_votes(&relayer, &other, &vec![&env, alice_vote]),
+        Error::InvalidNonce,
+    );
+
+    // Una firma de otra clave, o sobre otra opción, aborta el lote entero
+    let mut forged = sign(&bob_key, &alice, other, 0, 1);
+    assert!(client
+        .try_relay_votes(&relayer, &other, &vec![&env, forged.clone()])
+        .is_err());
+    forged = sign(&alice_key, &alice, other, 0, 1);
+    forged.option = 1;
+    assert!(client
+        .try_relay_votes(
+            &relayer,
+            &other,
+            &vec![&env, sign(&bob_key, &bob, other, 0, 1), forged]
+        )
+        .is_err());
+    assert!(!client.has_voted(&bob, &other));
+    assert_eq!(client.get_nonce(&bob), 1);
+
+    // La firma de otra propuesta tampoco vale aquí
+    let signed_for_other = sign(&alice_key, &alice, other, 0, 1);
+    let third = client.create_proposal(&creator, &si_no_config(&env));
+    assert!(client
+        .try_relay_votes(&relayer, &third, &vec![&env, signed_for_other.clone()])
+        .is_err());
+    client.relay_votes(&relayer, &other, &vec![&env, signed_for_other]);
+    assert_eq!(client.get_results(&other).votes, vec![&env, 1, 0]);
+
+    // Con token de gobernanza el peso sale del votante, así que no se admite relayer
+    let weighted_client = SimpleVotingClient::new(&env, &env.register(SimpleVoting, ()));
+    let token = env.register_stellar_asset_contract_v2(admin.clone());
+    weighted_client.init(&admin, &Some(token.address()));
+    let weighted = weighted_client.create_proposal(&creator, &si_no_config(&env));
+    weighted_client.register_voter_key(
+        &alice,
+        &BytesN::from_array(&env, &alice_key.verifying_key().to_bytes()),
+    );
+    let message = weighted_client.vote_message(&weighted, &0, &0);
+    let signature = alice_key.sign(&message.iter().collect::<std::vec::Vec<u8>>());
+    let weighted_vote = SignedVote {
+        voter: alice.clone(),
+        option: 0,
+        nonce: 0,
+        signature: BytesN::from_array(&env, &signature.to_bytes()),
+    };
+    assert_error(
+        &env,
+        weighted_client.try_relay_votes(&relayer, &weighted, &vec![&env, weighted_vote]),
+        Error::RelayNotAllowed,
+    );
+}
@@ -0,0 +1,9 @@
+;; Contrato mínimo usado como "siguiente versión" en el test de `upgrade`.
+;; Solo exporta `version`, que devuelve 2 como U32Val ((2 << 32) | 4).
+;;
+;; Regenerar con: wat2wasm upgrade_v2.wat -o upgrade_v2.wasm
+;; y añadir la sección `contractenvmetav0` (protocolo 22, sin pre-release):
+;; 00000000 00000016 00000000
+(module
+  (func (export "version") (result i64)
+    i64.const 8589934596))